@@ -1,4 +1,10 @@
 fn main() {
+    // Only the DLL and the loader need these, the remote client also builds
+    // for other platforms
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("windows") {
+        return;
+    }
+
     cc::Build::new()
         .cpp(true)
         .define("IMGUI_IMPL_API", "extern \"C\"")
@@ -0,0 +1,75 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::mpsc::{self, Receiver},
+};
+
+use anyhow::{anyhow, bail, Context};
+use serde_json::{json, Value};
+
+/// Called on the reader thread with the method and params of every
+/// notification.
+pub type NotificationHandler = Box<dyn FnMut(&str, &Value) + Send>;
+
+/// A connection to the tool's remote protocol, see `src/rpc.rs`.
+pub struct Connection {
+    writer: TcpStream,
+    responses: Receiver<Value>,
+    next_id: u64,
+}
+
+impl Connection {
+    pub fn connect(addr: &str, mut on_notification: NotificationHandler) -> anyhow::Result<Self> {
+        let writer = TcpStream::connect(addr)
+            .with_context(|| format!("failed to connect to {addr}, is the game running?"))?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        let (sender, responses) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    continue;
+                };
+                match message.get("method").and_then(Value::as_str) {
+                    Some(method) => on_notification(method, &message["params"]),
+                    None => {
+                        if sender.send(message).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self { writer, responses, next_id: 1 })
+    }
+
+    /// Sends a request and waits for its result.
+    pub fn request(&mut self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.writer, "{request}")?;
+
+        loop {
+            let mut response =
+                self.responses.recv().map_err(|_| anyhow!("the game closed the connection"))?;
+            if response["id"] != id {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                bail!("{}", error["message"].as_str().unwrap_or("unknown error"));
+            }
+            return Ok(response["result"].take());
+        }
+    }
+
+    /// Blocks until the game closes the connection, while notifications are
+    /// handled.
+    pub fn wait(self) {
+        while self.responses.recv().is_ok() {}
+    }
+}
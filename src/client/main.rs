@@ -0,0 +1,281 @@
+//! Command line client for the tool's remote protocol, started with
+//! `bg3-debug-tool.exe --port <port>`.
+
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+
+use self::connection::Connection;
+
+mod connection;
+mod repl;
+
+#[derive(Debug, Parser)]
+#[command(name = "bg3dbg", about = "Drives Baldur's Gate 3 through the debug tool's --port socket")]
+struct Args {
+    #[arg(long, global = true, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, global = true, default_value_t = 9003)]
+    port: u16,
+    /// Print the results as JSON, one line each
+    #[arg(long, global = true)]
+    json: bool,
+    /// Runs a single command, an interactive session is started without one
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Call an Osiris call or query, e.g. `call GetHostCharacter`
+    Call {
+        function: String,
+        /// Numbers, None and strings, quoted or not
+        #[arg(allow_negative_numbers = true)]
+        args: Vec<String>,
+    },
+    /// Search a Game Data Explorer category, with the same query syntax
+    Search {
+        category: String,
+        #[arg(long)]
+        limit: Option<usize>,
+        /// A query starting with a `-` negation goes after `--`
+        #[arg(allow_negative_numbers = true)]
+        query: Vec<String>,
+    },
+    /// Print every field of an object, identified by its GUID or name
+    Object { category: String, id: String },
+    /// List the game symbols the tool looked up, and how
+    Symbols,
+    /// Print the log as it is written
+    Log {
+        /// Start with what was logged before
+        #[arg(long)]
+        backlog: bool,
+        /// Stop printing it, in an interactive session
+        #[arg(long, conflicts_with = "backlog")]
+        stop: bool,
+    },
+    /// Print the Osiris events, calls and queries as they are traced
+    Trace {
+        /// Start with the entries recorded before
+        #[arg(long)]
+        backlog: bool,
+        /// Stop printing them, in an interactive session
+        #[arg(long, conflicts_with = "backlog")]
+        stop: bool,
+    },
+    /// Send a request with any method, params are given as JSON
+    Rpc { method: String, params: Option<String> },
+}
+
+fn main() {
+    let args = Args::parse();
+    let addr = format!("{}:{}", args.host, args.port);
+    let res = match args.command {
+        Some(command) => one_shot(&addr, command, args.json),
+        None => repl::run(&addr, args.json),
+    };
+    if let Err(x) = res {
+        eprintln!("error: {x:#}");
+        std::process::exit(1);
+    }
+}
+
+fn one_shot(addr: &str, command: Command, json: bool) -> anyhow::Result<()> {
+    let streaming =
+        matches!(command, Command::Log { stop: false, .. } | Command::Trace { stop: false, .. });
+    let mut connection = Connection::connect(
+        addr,
+        Box::new(move |method, params| print!("{}", format_notification(method, params, json))),
+    )?;
+    execute(&mut connection, command, json)?;
+    if streaming {
+        connection.wait();
+    }
+    Ok(())
+}
+
+/// Sends `command` and prints its result, subscriptions keep printing through
+/// the connection's notification handler.
+fn execute(connection: &mut Connection, command: Command, json: bool) -> anyhow::Result<()> {
+    let (method, params) = match &command {
+        Command::Call { function, args } => {
+            let args = args.iter().map(|x| parse_arg(x)).collect::<Vec<_>>();
+            ("osiris", json!({ "function": function, "args": args }))
+        }
+        Command::Search { category, limit, query } => {
+            ("search", json!({ "category": category, "query": query.join(" "), "limit": limit }))
+        }
+        Command::Object { category, id } => ("object", json!({ "category": category, "id": id })),
+        Command::Symbols => ("symbols", Value::Null),
+        Command::Log { stop: true, .. } => ("unsubscribe", json!({ "streams": ["log"] })),
+        Command::Trace { stop: true, .. } => ("unsubscribe", json!({ "streams": ["trace"] })),
+        Command::Log { backlog, .. } => {
+            ("subscribe", json!({ "streams": ["log"], "backlog": backlog }))
+        }
+        Command::Trace { backlog, .. } => {
+            ("subscribe", json!({ "streams": ["trace"], "backlog": backlog }))
+        }
+        Command::Rpc { method, params } => {
+            let params = params.as_deref().map(serde_json::from_str).transpose()?;
+            (method.as_str(), params.unwrap_or(Value::Null))
+        }
+    };
+
+    let result = connection.request(method, params)?;
+    if json {
+        println!("{result}");
+        return Ok(());
+    }
+    match command {
+        Command::Call { .. } if result.is_null() => (),
+        Command::Call { .. } => println!("{}", scalar(&result)),
+        Command::Search { .. } => {
+            let objects = result.as_array().map(Vec::as_slice).unwrap_or_default();
+            for object in objects {
+                println!("{}", summary(object));
+            }
+            println!("{} results", objects.len());
+        }
+        Command::Symbols => {
+            for entry in result.as_array().into_iter().flatten() {
+                let address = entry["address"].as_u64().map(|x| format!("{x:#X}"));
+                println!(
+                    "{:<48} {:<10} {}",
+                    scalar(&entry["symbol"]),
+                    scalar(&entry["status"]),
+                    address.unwrap_or_default()
+                );
+            }
+        }
+        Command::Log { .. } | Command::Trace { .. } => (),
+        Command::Object { .. } | Command::Rpc { .. } => {
+            println!("{}", serde_json::to_string_pretty(&result)?)
+        }
+    }
+    Ok(())
+}
+
+/// Integers, reals and `None` are passed as such, anything else as a string.
+fn parse_arg(arg: &str) -> Value {
+    if let Ok(x) = arg.parse::<i64>() {
+        json!(x)
+    } else if let Ok(x) = arg.parse::<f64>() {
+        json!(x)
+    } else if arg.eq_ignore_ascii_case("none") {
+        Value::Null
+    } else {
+        json!(arg)
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "None".into(),
+        Value::String(x) => x.clone(),
+        x => x.to_string(),
+    }
+}
+
+/// The names and GUID of a search result, or its first few plain fields.
+fn summary(object: &Value) -> String {
+    const FIELDS: usize = 3;
+    let Some(fields) = object.as_object() else {
+        return scalar(object);
+    };
+    let plain = || fields.iter().filter(|(_, x)| x.is_string());
+    let names = plain()
+        .filter(|(k, _)| k.contains("Name") || *k == "GUID" || *k == "Signature")
+        .map(|(_, x)| scalar(x))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        plain().take(FIELDS).map(|(_, x)| scalar(x)).collect::<Vec<_>>().join("  ")
+    } else {
+        names.join("  ")
+    }
+}
+
+fn format_notification(method: &str, params: &Value, json: bool) -> String {
+    if json {
+        return format!("{}\n", json!({ "method": method, "params": params }));
+    }
+    match method {
+        "log" => scalar(&params["text"]),
+        "trace" => params["entries"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|x| {
+                let args = x["Arguments"].as_array().into_iter().flatten().map(scalar);
+                format!(
+                    "{:>10} {:<5} {}({}) -> {}\n",
+                    scalar(&x["Time"]),
+                    scalar(&x["Kind"]),
+                    scalar(&x["Function"]),
+                    args.collect::<Vec<_>>().join(", "),
+                    scalar(&x["Result"])
+                )
+            })
+            .collect(),
+        _ => format!("{method}: {params}\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::try_parse_from([&["bg3dbg"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn args() {
+        assert_eq!(parse_arg("100"), json!(100));
+        assert_eq!(parse_arg("-5"), json!(-5));
+        assert_eq!(parse_arg("1.5"), json!(1.5));
+        assert_eq!(parse_arg("-0.5"), json!(-0.5));
+        assert_eq!(parse_arg("None"), Value::Null);
+        assert_eq!(parse_arg("none"), Value::Null);
+        assert_eq!(parse_arg("BURNING"), json!("BURNING"));
+        let guid = "S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c";
+        assert_eq!(parse_arg(guid), json!(guid));
+        assert_eq!(
+            parse_arg("1e4f6b2a-0d1c-4c3e-9f7a-5b2d8c6e0a13"),
+            json!("1e4f6b2a-0d1c-4c3e-9f7a-5b2d8c6e0a13")
+        );
+        assert_eq!(parse_arg(""), json!(""));
+    }
+
+    #[test]
+    fn global_flags_after_the_command() {
+        let args = parse(&["call", "AddGold", "-100", "--json", "--port", "9100"]);
+        assert!(args.json);
+        assert_eq!(args.port, 9100);
+        let Some(Command::Call { function, args }) = args.command else {
+            panic!("expected a call");
+        };
+        assert_eq!(function, "AddGold");
+        assert_eq!(args, ["-100"]);
+
+        let args = parse(&["--host", "10.0.0.2", "search", "Spells", "fire", "--json", "-1"]);
+        assert!(args.json);
+        assert_eq!(args.host, "10.0.0.2");
+        let Some(Command::Search { query, .. }) = args.command else {
+            panic!("expected a search");
+        };
+        assert_eq!(query, ["fire", "-1"]);
+
+        let args = parse(&["search", "Spells", "--json", "--", "-fire", "--limit"]);
+        assert!(args.json);
+        let Some(Command::Search { query, limit, .. }) = args.command else {
+            panic!("expected a search");
+        };
+        assert_eq!(query, ["-fire", "--limit"]);
+        assert_eq!(limit, None);
+
+        let args = parse(&["symbols"]);
+        assert!(!args.json);
+        assert_eq!((args.host.as_str(), args.port), ("127.0.0.1", 9003));
+    }
+}
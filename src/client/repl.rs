@@ -0,0 +1,243 @@
+use std::{borrow::Cow, collections::BTreeMap, path::PathBuf};
+
+use clap::Parser;
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, ExternalPrinter, Helper,
+};
+use serde_json::{json, Value as JsonValue};
+
+use super::{connection::Connection, execute, format_notification, Command};
+
+const HISTORY_FILE_NAME: &str = "bg3dbg_history.txt";
+const COMMANDS: [&str; 9] =
+    ["call", "search", "object", "symbols", "log", "trace", "rpc", "help", "exit"];
+
+/// A command typed in the interactive session.
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+struct Line {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Completes the commands, categories and Osiris function names, and hints
+/// at the signature of the function being called.
+#[derive(Debug, Default)]
+struct ReplHelper {
+    /// Signatures of the overloads by function name
+    functions: BTreeMap<String, Vec<String>>,
+    /// Game Data Explorer categories without spaces, the server ignores them
+    categories: Vec<String>,
+}
+
+impl ReplHelper {
+    fn load_categories(&mut self, connection: &mut Connection) {
+        let Ok(categories) = connection.request("categories", JsonValue::Null) else {
+            return;
+        };
+        self.categories = categories
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|x| x.as_str())
+            .map(|x| x.replace(' ', ""))
+            .collect();
+    }
+
+    /// The function list is empty until a save is loaded, so this is retried
+    /// after each command until it isn't.
+    fn load_functions(&mut self, connection: &mut Connection) {
+        let params = json!({ "category": "OsirisFunctions", "limit": u32::MAX });
+        let Ok(functions) = connection.request("search", params) else {
+            return;
+        };
+        for signature in
+            functions.as_array().into_iter().flatten().filter_map(|x| x["Signature"].as_str())
+        {
+            let name = signature.split('(').next().unwrap_or(signature);
+            self.functions.entry(name.into()).or_default().push(signature.into());
+        }
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let start = before.rfind(char::is_whitespace).map_or(0, |x| x + 1);
+        let word = before[start..].to_lowercase();
+
+        let candidates = match before[..start].split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] => COMMANDS.to_vec(),
+            ["call"] => self.functions.keys().map(String::as_str).collect(),
+            ["search" | "object"] => self.categories.iter().map(String::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let pairs = candidates
+            .into_iter()
+            .filter(|x| x.to_lowercase().starts_with(&word))
+            .map(|x| Pair { display: x.into(), replacement: format!("{x} ") })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        let mut words = line.split_whitespace();
+        if words.next() != Some("call") {
+            return None;
+        }
+        let signatures = self.functions.get(words.next()?)?;
+        Some(format!("    {}", signatures.join(" | ")))
+    }
+}
+
+impl Highlighter for ReplHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("\x1b[2m{hint}\x1b[0m"))
+    }
+}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+/// Reads commands until `exit` or Ctrl-D, notifications are printed above the
+/// prompt as they arrive.
+pub fn run(addr: &str, json: bool) -> anyhow::Result<()> {
+    let mut editor = Editor::<ReplHelper, DefaultHistory>::new()?;
+    let mut printer = editor.create_external_printer()?;
+    let mut connection = Connection::connect(
+        addr,
+        Box::new(move |method, params| {
+            let _ = printer.print(format_notification(method, params, json));
+        }),
+    )?;
+    println!("Connected to {addr}, type 'help' for the commands");
+
+    let mut helper = ReplHelper::default();
+    helper.load_categories(&mut connection);
+    helper.load_functions(&mut connection);
+    editor.set_helper(Some(helper));
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline("bg3> ") {
+            Ok(x) => x,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(x) => return Err(x.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+        if matches!(line, "exit" | "quit") {
+            break;
+        }
+
+        match Line::try_parse_from(split_args(line)) {
+            Ok(x) => {
+                if let Err(x) = execute(&mut connection, x.command, json) {
+                    eprintln!("error: {x:#}");
+                }
+            }
+            Err(x) => {
+                let _ = x.print();
+            }
+        }
+        if let Some(helper) = editor.helper_mut() {
+            if helper.functions.is_empty() {
+                helper.load_functions(&mut connection);
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+/// Kept next to the client.
+fn history_path() -> Option<PathBuf> {
+    Some(std::env::current_exe().ok()?.with_file_name(HISTORY_FILE_NAME))
+}
+
+/// Splits on whitespace, except in double quotes, `\` escapes the next
+/// character.
+fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = None::<String>;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => arg.get_or_insert_default().extend(chars.next()),
+            '"' => {
+                quoted = !quoted;
+                arg.get_or_insert_default();
+            }
+            x if x.is_whitespace() && !quoted => args.extend(arg.take()),
+            x => arg.get_or_insert_default().push(x),
+        }
+    }
+    args.extend(arg);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        assert_eq!(split_args("call  AddGold\t100 "), ["call", "AddGold", "100"]);
+        assert_eq!(split_args(""), Vec::<String>::new());
+        assert_eq!(split_args("   "), Vec::<String>::new());
+        assert_eq!(split_args("search Spells \"Fire Bolt\" x"), [
+            "search",
+            "Spells",
+            "Fire Bolt",
+            "x"
+        ]);
+        assert_eq!(split_args("a\"b c\"d"), ["ab cd"]);
+        assert_eq!(split_args("call F \"\""), ["call", "F", ""]);
+        assert_eq!(split_args("\"a \\\" b\" c\\ d"), ["a \" b", "c d"]);
+        assert_eq!(split_args("\"unterminated quote"), ["unterminated quote"]);
+        assert_eq!(split_args("trailing\\"), ["trailing"]);
+    }
+
+    #[test]
+    fn lines() {
+        let line = Line::try_parse_from(split_args("call AddGold -5 \"a b\"")).unwrap();
+        let Command::Call { function, args } = line.command else {
+            panic!("expected a call");
+        };
+        assert_eq!(function, "AddGold");
+        assert_eq!(args, ["-5", "a b"]);
+        assert!(Line::try_parse_from(split_args("frobnicate")).is_err());
+    }
+}
@@ -7,6 +7,7 @@ mod loc;
 mod osiris;
 mod prototype;
 mod root_templates;
+mod stats;
 
 pub(crate) use common::*;
 pub(crate) use cpp::*;
@@ -16,3 +17,4 @@ pub(crate) use loc::*;
 pub(crate) use osiris::*;
 pub(crate) use prototype::*;
 pub(crate) use root_templates::*;
+pub(crate) use stats::*;
@@ -1,10 +1,13 @@
 use std::{
     alloc,
+    borrow::Cow,
     ffi::CStr,
     fmt::{Debug, Display},
     marker,
 };
 
+use bitflags::bitflags;
+use game_object::TableValue;
 use itertools::Itertools;
 
 use super::{GamePtr, PtrOrBuf};
@@ -17,10 +20,10 @@ pub(crate) struct OsirisStaticGlobals {
     pub enums: *const (),
     pub functions: GamePtr<GamePtr<FunctionDb>>,
     pub objects: *const (),
-    pub goals: *const (),
+    pub goals: GamePtr<GamePtr<GoalDb>>,
     pub adapters: *const (),
-    pub databases: *const (),
-    pub nodes: *const (),
+    pub databases: GamePtr<GamePtr<DatabaseDb>>,
+    pub nodes: GamePtr<GamePtr<NodeDb>>,
 }
 
 #[derive(Debug)]
@@ -458,6 +461,237 @@ impl Display for FunctionType {
     }
 }
 
+/// Osiris keeps nodes and databases in vectors indexed by `id - 1`.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct TypedDb<T> {
+    vptr: *const (),
+    pub elements: OsiVec<GamePtr<T>>,
+}
+
+impl<T> TypedDb<T> {
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.elements.as_slice().get(id.checked_sub(1)? as usize)?.as_opt()
+    }
+}
+
+pub(crate) type NodeDb = TypedDb<Node>;
+pub(crate) type DatabaseDb = TypedDb<Database>;
+pub(crate) type GoalDb = TypedDb<Goal>;
+
+impl Function {
+    /// Database storing the facts of a `Database` function.
+    pub fn database(&self, nodes: &NodeDb, databases: &DatabaseDb) -> Option<&'static Database> {
+        let node = nodes.get(self.node.id)?;
+        let db = databases.get(node.database.id)?;
+        Some(unsafe { &*(db as *const Database) })
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Node {
+    vptr: *const (),
+    pub id: u32,
+    pub function: GamePtr<Function>,
+    pub database: DatabaseRef,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct DatabaseRef {
+    pub id: u32,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Database {
+    pub id: u32,
+    unknown: u32,
+    pub facts: List<TupleVec>,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct TupleVec {
+    pub size: u32,
+    unknown: u32,
+    pub values: *const TypedValue,
+}
+
+impl TupleVec {
+    pub fn as_slice(&self) -> &[TypedValue] {
+        if self.values.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.values, self.size as _) }
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct TypedValue {
+    vptr: *const (),
+    pub type_id: u32,
+    pub value: OsiArgumentValueUnion,
+}
+
+impl TypedValue {
+    /// NULL strings, e.g. of deleted objects, are converted to `None`.
+    pub fn as_argument(&self) -> OsiArgumentValue {
+        let type_id = ValueType::from(self.type_id as u16);
+        if type_id as u16 > ValueType::Real as u16
+            && type_id != ValueType::Undefined
+            && unsafe { self.value.string.is_null() }
+        {
+            return OsiArgumentValue::none();
+        }
+        OsiArgumentValue { value: self.value, type_id, unknown: false }
+    }
+}
+
+impl Display for TypedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let type_id = ValueType::from(self.type_id as u16);
+        if type_id as u16 > ValueType::Real as u16
+            && type_id != ValueType::Undefined
+            && unsafe { self.value.string.is_null() }
+        {
+            return f.write_str("NULL");
+        }
+        Display::fmt(&OsiArgumentValue { value: self.value, type_id, unknown: false }, f)
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Goal {
+    pub id: u32,
+    pub name: *const i8,
+    pub sub_goal_combination: u32,
+    pub parent_goals: List<GoalRef>,
+    pub sub_goals: List<GoalRef>,
+    pub flags: GoalFlags,
+    pub init_calls: GamePtr<RuleActionList>,
+    pub exit_calls: GamePtr<RuleActionList>,
+}
+
+impl Goal {
+    pub fn name(&self) -> Cow<'_, str> {
+        if self.name.is_null() {
+            return Cow::Borrowed("");
+        }
+        unsafe { CStr::from_ptr(self.name) }.to_string_lossy()
+    }
+
+    pub fn state(&self) -> GoalState {
+        if self.flags.contains(GoalFlags::Completed) {
+            GoalState::Completed
+        } else if self.flags.contains(GoalFlags::Active) {
+            GoalState::Active
+        } else {
+            GoalState::Sleeping
+        }
+    }
+
+    pub fn init_calls(&self) -> impl Iterator<Item = &RuleAction> {
+        self.init_calls.as_opt().into_iter().flat_map(|x| x.actions.iter()).map(|x| x.as_ref())
+    }
+
+    pub fn exit_calls(&self) -> impl Iterator<Item = &RuleAction> {
+        self.exit_calls.as_opt().into_iter().flat_map(|x| x.actions.iter()).map(|x| x.as_ref())
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub(crate) struct GoalFlags: u8 {
+        const Active = 0x1;
+        const Completed = 0x2;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TableValue)]
+pub(crate) enum GoalState {
+    Sleeping,
+    Active,
+    Completed,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct GoalRef {
+    pub id: u32,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct RuleActionList {
+    vptr: *const (),
+    pub actions: List<GamePtr<RuleAction>>,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct RuleAction {
+    vptr: *const (),
+    pub function_name: *const i8,
+    pub arguments: GamePtr<TupleLL>,
+    pub not: bool,
+    pub goal_id_or_debug_hook: i32,
+}
+
+impl Display for RuleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.not {
+            f.write_str("NOT ")?;
+        }
+        if !self.function_name.is_null() {
+            f.write_str(&unsafe { CStr::from_ptr(self.function_name) }.to_string_lossy())?;
+        }
+        let args = self.arguments.as_opt().into_iter().flat_map(|x| x.items.iter());
+        write!(
+            f,
+            "({})",
+            args.map(|x| x.value.as_opt().map(|x| x.to_string()))
+                .map(Option::unwrap_or_default)
+                .join(", ")
+        )
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct TupleLL {
+    vptr: *const (),
+    pub items: List<TupleLLItem>,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct TupleLLItem {
+    pub index: u8,
+    pub value: GamePtr<TypedValue>,
+}
+
+/// MSVC `std::vector`.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct OsiVec<T> {
+    start: *const T,
+    end: *const T,
+    capacity_end: *const T,
+}
+
+impl<T> OsiVec<T> {
+    pub fn as_slice(&self) -> &[T] {
+        if self.start.is_null() || self.end < self.start {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.start, self.end.offset_from(self.start) as _) }
+    }
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub(crate) struct TMap<K: PartialOrd, V> {
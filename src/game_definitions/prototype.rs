@@ -377,3 +377,26 @@ pub(crate) struct PassivePrototype {
     pub priority_order: i32,
     pub tooltip_conditional_damage: FixedString,
 }
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct InterruptPrototypeManager {
+    vptr: *const (),
+    pub interrupts: MultiHashMap<FixedString, InterruptPrototype>,
+    pub initialized: bool,
+}
+
+/// Stored inline in the manager, `eoc::InterruptPrototype::Init` is called on
+/// 0x220 byte strides.
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct InterruptPrototype {
+    pub stats_object_index: i32,
+    pub interrupt_context: u8,
+    pub interrupt_context_scope: u8,
+    pub container: FixedString,
+    pub interrupt_flags: u8,
+    pub description: DescriptionInfo,
+    /// Conditions, roll, properties and costs
+    field_b0: [u8; 0x170],
+}
@@ -5,7 +5,7 @@ use x86_64::registers::segmentation::Segment64;
 
 use super::{
     glm, Array, FixedString, GamePtr, Guid, Map, MultiHashMap, MultiHashSet, OverrideableProperty,
-    STDString, Transform, TranslatedString,
+    STDString, SurfaceType, Transform, TranslatedString,
 };
 
 #[derive(Debug)]
@@ -46,36 +46,41 @@ pub(crate) enum Template<'a> {
     EoCGameObject(&'a EoCGameObjectTemplate),
     Scenery(&'a SceneryTemplate),
     Item(&'a ItemTemplate),
+    Character(&'a CharacterTemplate),
+    Trigger(&'a TriggerTemplate),
+    Projectile(&'a ProjectileTemplate),
+    Surface(&'a SurfaceTemplate),
+    Prefab(&'a PrefabTemplate),
+    Light(&'a LightTemplate),
+    Level(&'a LevelTemplate),
 }
 
 impl<'a> From<&'a GameObjectTemplate> for Template<'a> {
     fn from(value: &'a GameObjectTemplate) -> Self {
         let r#type = value.get_type().as_str();
-        // possible types
+        // other types, kept as GameObject
         //
         // CombinedLight
-        // LevelTemplate
         // Schematic
         // Spline
         // TileConstruction
-        // character
         // constellation
         // constellationHelper
         // decal
         // fogVolume
-        // item
-        // light
         // lightProbe
-        // prefab
-        // projectile
-        // scenery
-        // surface
         // terrain
-        // trigger
         unsafe {
             match r#type {
                 "item" => Self::Item(mem::transmute(value)),
                 "scenery" => Self::Scenery(mem::transmute(value)),
+                "character" => Self::Character(mem::transmute(value)),
+                "trigger" => Self::Trigger(mem::transmute(value)),
+                "projectile" => Self::Projectile(mem::transmute(value)),
+                "surface" => Self::Surface(mem::transmute(value)),
+                "prefab" => Self::Prefab(mem::transmute(value)),
+                "light" => Self::Light(mem::transmute(value)),
+                "LevelTemplate" => Self::Level(mem::transmute(value)),
                 _ => Self::GameObject(mem::transmute(value)),
             }
         }
@@ -329,6 +334,215 @@ impl Deref for ItemTemplate {
     }
 }
 
+/// Only the leading fields are modeled, the rest of the template (visuals,
+/// animation sets, footsteps, etc.) is left out.
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct CharacterTemplate {
+    #[column(visible)]
+    pub base: EoCGameObjectTemplate,
+    pub combat_component: CombatComponentTemplate,
+    pub speaker_groups: GamePtr<MultiHashSet<Guid>>,
+    pub icon: OverrideableProperty<FixedString>,
+    pub stats: OverrideableProperty<FixedString>,
+    pub spell_set: OverrideableProperty<FixedString>,
+    pub equipment: OverrideableProperty<FixedString>,
+    pub treasures: OverrideableProperty<Array<FixedString>>,
+    pub trade_treasures: OverrideableProperty<Array<FixedString>>,
+    pub light_id: OverrideableProperty<FixedString>,
+    pub activation_group_id: OverrideableProperty<FixedString>,
+    pub search_display_name: OverrideableProperty<TranslatedString>,
+    pub sound_init_event: OverrideableProperty<FixedString>,
+    pub sound_move_start_event: OverrideableProperty<FixedString>,
+    pub sound_move_stop_event: OverrideableProperty<FixedString>,
+    pub sound_object_index: OverrideableProperty<i8>,
+    pub sound_attachment_bone: OverrideableProperty<FixedString>,
+    pub sound_attenuation: OverrideableProperty<i16>,
+    pub blood_type: OverrideableProperty<FixedString>,
+    pub critical_hit_type: OverrideableProperty<FixedString>,
+    pub default_state: OverrideableProperty<FixedString>,
+    pub is_equipment_lootable: OverrideableProperty<bool>,
+    pub is_lootable_when_equipped: OverrideableProperty<bool>,
+    pub is_player: OverrideableProperty<bool>,
+    pub walk_speed_override: OverrideableProperty<f32>,
+    pub run_speed_override: OverrideableProperty<f32>,
+    pub race: OverrideableProperty<Guid>,
+    pub default_dialog: OverrideableProperty<Guid>,
+    pub title: OverrideableProperty<TranslatedString>,
+    pub character_visual_resource_id: OverrideableProperty<FixedString>,
+}
+
+impl Deref for CharacterTemplate {
+    type Target = EoCGameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+/// Only the leading fields are modeled, like the other templates below.
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct TriggerTemplate {
+    #[column(visible)]
+    pub base: GameObjectTemplate,
+    #[column(visible, include_in_search)]
+    pub trigger_type: FixedString,
+    pub physics_type: u32,
+    pub trigger_gizmo_override: FixedString,
+    pub color: glm::Vec3,
+    pub sync_with_client: bool,
+    pub is_ai_hint: bool,
+}
+
+impl Deref for TriggerTemplate {
+    type Target = GameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct ProjectileTemplate {
+    #[column(visible)]
+    pub base: EoCGameObjectTemplate,
+    pub life_time: f32,
+    #[column(visible)]
+    pub speed: f32,
+    pub acceleration: f32,
+    pub cast_bone: FixedString,
+    #[column(visible, include_in_search)]
+    pub impact_fx: FixedString,
+    pub trail_fx: FixedString,
+    pub destroy_trail_fx_on_impact: bool,
+    pub beam_fx: FixedString,
+    pub preview_path_material: FixedString,
+    pub preview_path_impact_fx: FixedString,
+    pub preview_path_radius: f32,
+    pub impact_fx_size: f32,
+    pub rotate_impact: bool,
+    pub ignore_roof: bool,
+    pub detach_beam: bool,
+    pub needs_arrow_impact_sfx: bool,
+    pub projectile_path: FixedString,
+    pub path_shift: FixedString,
+    pub path_radius: FixedString,
+    pub path_min_arc_dist: FixedString,
+    pub path_max_arc_dist: FixedString,
+    pub path_repeat: FixedString,
+}
+
+impl Deref for ProjectileTemplate {
+    type Target = EoCGameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct SurfaceTemplate {
+    #[column(visible)]
+    pub base: GameObjectTemplate,
+    pub surface_type_id: i32,
+    #[column(visible)]
+    pub surface_type: SurfaceType,
+    #[column(visible)]
+    pub display_name: TranslatedString,
+    pub description: TranslatedString,
+    pub decal_material: FixedString,
+    pub can_enter_combat: bool,
+    pub always_use_default_life_time: bool,
+    pub default_life_time: f32,
+    pub surface_grow_timer: f32,
+    pub fade_in_speed: f32,
+    pub fade_out_speed: f32,
+    pub seed: i32,
+}
+
+impl Deref for SurfaceTemplate {
+    type Target = GameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct PrefabTemplate {
+    #[column(visible)]
+    pub base: GameObjectTemplate,
+    /// Ids of the templates the prefab places
+    #[column(visible)]
+    pub children: Array<FixedString>,
+}
+
+impl Deref for PrefabTemplate {
+    type Target = GameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct LightTemplate {
+    #[column(visible)]
+    pub base: GameObjectTemplate,
+    #[column(visible)]
+    pub light_type: u8,
+    #[column(visible)]
+    pub color: glm::Vec3,
+    #[column(visible)]
+    pub intensity: f32,
+    pub radius: f32,
+    pub kelvin: f32,
+    pub use_temperature: bool,
+    pub modulate: bool,
+    pub is_sunlight: bool,
+    pub volumetric_light_intensity: f32,
+    pub volumetric_light_collision_probability: f32,
+    pub flicker_speed: f32,
+    pub flicker_amount: f32,
+    pub movement_speed: f32,
+    pub movement_amount: f32,
+}
+
+impl Deref for LightTemplate {
+    type Target = GameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct LevelTemplate {
+    #[column(visible)]
+    pub base: GameObjectTemplate,
+    #[column(visible, include_in_search)]
+    pub sub_level_name: FixedString,
+    pub is_persistent: bool,
+    pub world_bound_min: glm::Vec3,
+    pub world_bound_max: glm::Vec3,
+    pub local_bound_min: glm::Vec3,
+    pub local_bound_max: glm::Vec3,
+}
+
+impl Deref for LevelTemplate {
+    type Target = GameObjectTemplate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
 #[derive(GameObject)]
 #[repr(C)]
 pub(crate) struct EquipmentData {
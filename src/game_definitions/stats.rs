@@ -0,0 +1,92 @@
+use game_object::GameObject;
+
+use super::{Array, FixedString, GamePtr, Guid, MultiHashMap};
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct RPGStats {
+    vptr: *const (),
+    pub modifier_value_lists: NamedElementManager<RPGEnumeration>,
+    pub modifier_lists: NamedElementManager<ModifierList>,
+    pub objects: NamedElementManager<StatsObject>,
+    pub treasure_categories: NamedElementManager<()>,
+    pub treasure_tables: NamedElementManager<()>,
+    pub item_types: NamedElementManager<()>,
+    field_248: [u8; 0xD0],
+    pub fixed_strings: Array<FixedString>,
+    pub int64s: Array<GamePtr<i64>>,
+    pub floats: Array<f32>,
+    pub guids: Array<Guid>,
+}
+
+/// Elements owned by index, with an index by name.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct NamedElementManager<T> {
+    vptr: *const (),
+    pub primitives: Array<GamePtr<T>>,
+    pub name_hash_map: MultiHashMap<FixedString, u32>,
+    pub next_handle: u32,
+    pub num_some_items: u32,
+}
+
+impl<T> NamedElementManager<T> {
+    pub fn get(&self, index: i32) -> Option<&T> {
+        self.primitives.get(usize::try_from(index).ok()?)?.as_opt()
+    }
+
+    pub fn find(&self, name: &FixedString) -> Option<&T> {
+        self.get(*self.name_hash_map.try_get(name)? as _)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.primitives.iter().filter_map(|x| x.as_opt())
+    }
+}
+
+/// Value type of a modifier, the values are empty for the types that aren't
+/// enumerations, e.g. `ConstantInt` or `FixedString`.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct RPGEnumeration {
+    pub name: FixedString,
+    pub values: MultiHashMap<FixedString, i32>,
+}
+
+/// Properties of one stats entry type, e.g. `Weapon` or `SpellData`.
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct ModifierList {
+    pub attributes: NamedElementManager<Modifier>,
+    pub name: FixedString,
+}
+
+#[derive(Debug)]
+#[repr(C)]
+pub(crate) struct Modifier {
+    /// Index into [`RPGStats::modifier_value_lists`]
+    pub enumeration_index: i32,
+    pub level_map_index: i32,
+    field_8: i32,
+    pub name: FixedString,
+}
+
+#[derive(GameObject)]
+#[repr(C)]
+pub(crate) struct StatsObject {
+    vptr: *const (),
+    /// By modifier index, an index into the pool of the modifier's type, the
+    /// value itself for `ConstantInt` and enumerations
+    pub indexed_properties: Array<i32>,
+    pub name: FixedString,
+    functors: MultiHashMap<FixedString, Array<*const ()>>,
+    roll_conditions: MultiHashMap<FixedString, Array<*const ()>>,
+    pub ai_flags: FixedString,
+    requirements: Array<*const ()>,
+    pub combo_properties: Array<FixedString>,
+    pub combo_categories: Array<FixedString>,
+    /// Index of the object it inherits from, `-1` for none
+    pub using: i32,
+    pub modifier_list_index: u32,
+    pub level: u32,
+}
@@ -50,6 +50,7 @@ pub fn game_object(item: TokenStream) -> TokenStream {
     let mut visit_field = Vec::new();
     let mut visit_all = Vec::new();
     let mut visit_parallel = Vec::new();
+    let mut field_str = Vec::new();
 
     let mut tbl_cmp = vec![quote!(std::cmp::Ordering::Equal)];
 
@@ -106,6 +107,10 @@ pub fn game_object(item: TokenStream) -> TokenStream {
             )),
         );
 
+        field_str.push(quote!(if crate::menu::search::query::field_name_eq(name, #name) {
+            return crate::menu::search::query::field_str(#get_self, rest);
+        }));
+
         tbl_cmp.push(quote!(.then_with(|| (*#get_self).tbl_cmp(#get_other))));
 
         debug.push(quote!(.field(&#name, #get_self)));
@@ -183,6 +188,19 @@ pub fn game_object(item: TokenStream) -> TokenStream {
                 fn is_container(&self) -> bool {
                     true
                 }
+
+                fn field_str(&self, path: &[String]) -> Option<String> {
+                    let (name, rest) = path.split_first()?;
+                    #(#field_str)*
+                    None
+                }
+
+                fn export(
+                    &self,
+                    ctx: &mut crate::menu::search::export::ExportCtx,
+                ) -> crate::menu::search::export::ExportValue {
+                    crate::menu::search::export::export_item(self, ctx)
+                }
             }
 
             impl crate::menu::search::table_value::TableOrd for #r#type {
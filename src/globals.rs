@@ -3,99 +3,43 @@ use std::{
     sync::{Mutex, MutexGuard},
 };
 
-use crate::{binary_mappings::StaticSymbols, game_definitions::OsirisStaticGlobals};
-
-#[macro_export]
-macro_rules! info {
-    ($($tt:tt)*) => {
-        {
-            $crate::_print!("\x1b[1m");
-            $crate::_log_print!("[INFO]: ");
-            $crate::_log_println!($($tt)*);
-            $crate::_print!($($tt)*);
-            $crate::_println!("\x1b[0m")
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! warn {
-    ($($tt:tt)*) => {
-        {
-            $crate::_print!("\x1b[33m");
-            $crate::_log_print!("[WARNING]: ");
-            $crate::_log_println!($($tt)*);
-            $crate::_print!($($tt)*);
-            $crate::_println!("\x1b[0m")
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! err {
-    ($($tt:tt)*) => {
-        {
-            $crate::_print!("\x1b[31m");
-            $crate::_log_print!("[ERROR]: ");
-            $crate::_log_println!($($tt)*);
-            $crate::_print!($($tt)*);
-            $crate::_println!("\x1b[0m")
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! _print {
-    ($($tt:tt)*) => {
-        {
-            use std::io::Write;
-            write!($crate::globals::Globals::io_mut(), $($tt)*).unwrap();
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! _println {
-    ($($tt:tt)*) => {
-        {
-            use std::io::Write;
-            writeln!($crate::globals::Globals::io_mut(), $($tt)*).unwrap();
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! _log_print {
-    ($($tt:tt)*) => {
-        $crate::globals::Globals::log().push_str(&format!($($tt)*));
-    };
-}
-
-#[macro_export]
-macro_rules! _log_println {
-    ($($tt:tt)*) => {
-        $crate::_log_print!($($tt)*);
-        $crate::globals::Globals::log().push('\n');
-    };
-}
+#[cfg(not(feature = "osi-no-debugger"))]
+use crate::wrappers::debugger::Debugger;
+use crate::{
+    binary_mappings::{StaticSymbols, SymbolReport},
+    game_definitions::OsirisStaticGlobals,
+    log::Logger,
+    rpc::{self, game::Connections},
+    wrappers::trace::Tracer,
+};
 
 static mut GLOBALS: Globals = Globals::new();
 
 #[derive(Debug, Default)]
 pub(crate) struct Globals {
     static_symbols: StaticSymbols,
+    symbol_report: SymbolReport,
     osiris_globals: Option<OsirisStaticGlobals>,
     io: Option<Io>,
-    log: Mutex<String>,
+    log: Mutex<Logger>,
+    trace: Mutex<Tracer>,
+    #[cfg(not(feature = "osi-no-debugger"))]
+    debugger: Mutex<Debugger>,
+    rpc: Mutex<Connections>,
 }
 
 impl Globals {
     pub const fn new() -> Self {
         Self {
             static_symbols: StaticSymbols::new(),
+            symbol_report: SymbolReport::new(),
             osiris_globals: None,
             io: None,
-            log: Mutex::new(String::new()),
+            log: Mutex::new(Logger::new()),
+            trace: Mutex::new(Tracer::new()),
+            #[cfg(not(feature = "osi-no-debugger"))]
+            debugger: Mutex::new(Debugger::new()),
+            rpc: Mutex::new(Connections::new()),
         }
     }
 
@@ -107,10 +51,22 @@ impl Globals {
         unsafe { &mut GLOBALS.static_symbols }
     }
 
+    pub fn symbol_report() -> &'static SymbolReport {
+        unsafe { &GLOBALS.symbol_report }
+    }
+
+    pub fn symbol_report_set(v: SymbolReport) {
+        unsafe { GLOBALS.symbol_report = v }
+    }
+
     pub fn osiris_globals() -> &'static OsirisStaticGlobals {
         unsafe { GLOBALS.osiris_globals.as_ref().expect("osiris_globals not initialized") }
     }
 
+    pub fn osiris_globals_opt() -> Option<&'static OsirisStaticGlobals> {
+        unsafe { GLOBALS.osiris_globals.as_ref() }
+    }
+
     pub fn osiris_globals_mut() -> &'static mut OsirisStaticGlobals {
         unsafe { GLOBALS.osiris_globals.as_mut().expect("osiris_globals not initialized") }
     }
@@ -133,22 +89,38 @@ impl Globals {
         unsafe { GLOBALS.io = v }
     }
 
-    pub fn log() -> MutexGuard<'static, String> {
+    pub fn log() -> MutexGuard<'static, Logger> {
         unsafe { GLOBALS.log.lock().unwrap() }
     }
+
+    pub fn trace() -> MutexGuard<'static, Tracer> {
+        unsafe { GLOBALS.trace.lock().unwrap() }
+    }
+
+    #[cfg(not(feature = "osi-no-debugger"))]
+    pub fn debugger() -> MutexGuard<'static, Debugger> {
+        unsafe { GLOBALS.debugger.lock().unwrap() }
+    }
+
+    pub fn rpc() -> MutexGuard<'static, Connections> {
+        unsafe { GLOBALS.rpc.lock().unwrap() }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) enum Io {
     StdIo(io::StdinLock<'static>, io::Stdout),
-    Tcp(io::BufReader<net::TcpStream>, std::sync::Mutex<net::TcpStream>),
+    /// Remote connections are served in the background, see [`Io::tcp`]
+    Tcp(net::SocketAddr),
 }
 
+/// Remote connections send requests through the protocol, there is nothing
+/// to read.
 impl io::Read for Io {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match self {
             Io::StdIo(stdin, _) => stdin.read(buf),
-            Io::Tcp(s, _) => s.read(buf),
+            Io::Tcp(_) => Ok(0),
         }
     }
 }
@@ -157,30 +129,34 @@ impl io::BufRead for Io {
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
         match self {
             Io::StdIo(stdin, _) => stdin.fill_buf(),
-            Io::Tcp(s, _) => s.fill_buf(),
+            Io::Tcp(_) => Ok(&[]),
         }
     }
 
     fn consume(&mut self, amt: usize) {
         match self {
             Io::StdIo(stdin, _) => stdin.consume(amt),
-            Io::Tcp(s, _) => s.consume(amt),
+            Io::Tcp(_) => (),
         }
     }
 }
 
+/// The socket carries the remote protocol, the log reaches it as
+/// notifications to the connections subscribed to it. Its records are kept,
+/// so what was logged before anyone connected is sent to those that
+/// subscribe with `backlog`.
 impl io::Write for Io {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self {
             Io::StdIo(_, stdout) => stdout.write(buf),
-            Io::Tcp(_, s) => s.get_mut().unwrap().write(buf),
+            Io::Tcp(_) => Ok(buf.len()),
         }
     }
 
     fn flush(&mut self) -> io::Result<()> {
         match self {
             Io::StdIo(_, stdout) => stdout.flush(),
-            Io::Tcp(_, s) => s.get_mut().unwrap().flush(),
+            Io::Tcp(_) => Ok(()),
         }
     }
 }
@@ -190,10 +166,9 @@ impl Io {
         Self::StdIo(io::stdin().lock(), io::stdout())
     }
 
-    pub fn tcp(addr: impl net::ToSocketAddrs) -> Self {
-        let listener = net::TcpListener::bind(addr).unwrap();
-        let c = listener.accept().unwrap().0;
-
-        Self::Tcp(io::BufReader::new(c.try_clone().unwrap()), std::sync::Mutex::new(c))
+    /// Listens on `addr` without waiting for a connection, any number of
+    /// clients can connect and reconnect while the game runs.
+    pub fn tcp(addr: impl net::ToSocketAddrs) -> io::Result<Self> {
+        rpc::game::listen(addr).map(Self::Tcp)
     }
 }
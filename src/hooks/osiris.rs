@@ -1,15 +1,18 @@
 use std::ptr;
 
+use symbol_mappings::asm::{self, Instruction, OpcodeMap};
 use windows::{
     core::{s, w},
     Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW},
 };
 
 use crate::{
+    binary_mappings::ModuleInfo,
     err, fn_definitions,
     game_definitions::{GamePtr, OsiArgumentDesc, OsirisStaticGlobals},
     globals::Globals,
-    hook_definitions,
+    hook_definitions, hooks,
+    wrappers::trace::{self, TraceKind},
 };
 
 pub(crate) fn init() -> anyhow::Result<()> {
@@ -27,12 +30,38 @@ osiris("Osiris.dll") {
 
             Globals::osiris_globals_set(find_osiris_globals(osiris_ctor_proc));
 
-            FUNCS.Call.set(*(b as *const *const ()).add(1));
-            FUNCS.Query.set(*(b as *const *const ()).add(2));
+            let call = *(b as *const *const ()).add(1);
+            let query = *(b as *const *const ()).add(2);
+            FUNCS.Call.set(call);
+            FUNCS.Query.set(query);
+
+            // Only known once Osiris registers them, everything going through
+            // the entry points is traced, including the console's calls
+            hooks::detour(|| {
+                HOOKS.TraceCall.attach(call);
+                HOOKS.TraceQuery.attach(query);
+            });
 
             original::RegisterDivFunctions(a, b)
         }
     }
+
+    #[symbol_name = "?Event@COsiris@@QEAA_NIPEAVCOsiArgumentDesc@@@Z"]
+    fn Event(osiris: *const u8, function_id: u32, params: GamePtr<OsiArgumentDesc>) -> bool {
+        dispatch(TraceKind::Event, function_id, params, || {
+            original::Event(osiris, function_id, params)
+        })
+    }
+
+    #[no_init = yes]
+    fn TraceCall(handle: u32, params: GamePtr<OsiArgumentDesc>) -> bool {
+        dispatch(TraceKind::Call, handle, params, || original::TraceCall(handle, params))
+    }
+
+    #[no_init = yes]
+    fn TraceQuery(handle: u32, params: GamePtr<OsiArgumentDesc>) -> bool {
+        dispatch(TraceKind::Query, handle, params, || original::TraceQuery(handle, params))
+    }
 }
 }
 
@@ -46,24 +75,53 @@ osiris("Osiris.dll") {
 }
 }
 
+/// Runs a hooked Osiris function under the debugger and records it in the
+/// trace.
+fn dispatch(
+    kind: TraceKind,
+    handle: u32,
+    params: GamePtr<OsiArgumentDesc>,
+    f: impl FnOnce() -> bool,
+) -> bool {
+    #[cfg(not(feature = "osi-no-debugger"))]
+    let armed = crate::wrappers::debugger::is_armed();
+    #[cfg(feature = "osi-no-debugger")]
+    let armed = false;
+    // The name is only needed by the trace and breakpoints
+    if !armed && !trace::is_recording() {
+        return f();
+    }
+
+    let (name, r#type) = Globals::trace().resolve(handle);
+    #[cfg(not(feature = "osi-no-debugger"))]
+    let _frame = crate::wrappers::debugger::enter(kind, &name, params);
+    let res = f();
+    Globals::trace().record(kind, name, r#type, params, res);
+    res
+}
+
 unsafe fn find_osiris_globals(ctor_proc: *const u8) -> Option<OsirisStaticGlobals> {
-    let addr = resolve_real_function_address(ctor_proc);
+    let module = ModuleInfo::load("Osiris.dll").ok()?;
+    let mem = std::slice::from_raw_parts(module.start, module.size);
+    let ctor = resolve_real_function_address(mem, ctor_proc.offset_from(module.start) as _);
 
     let mut globals = [ptr::null::<()>(); 9];
     let mut found_globals = 0;
 
-    for i in 0..0x500 {
-        let ptr = addr.add(i);
-
-        if (ptr.read_unaligned() == 0x49 || ptr.read_unaligned() == 0x48)
-            && ptr.add(1).read_unaligned() == 0x8B
-            && ptr.add(3).read_unaligned() == 0x48
-            && ptr.add(4).read_unaligned() == 0x89
-            && (ptr.add(5).read_unaligned() & 0xC7) == 0x05
+    // The constructor allocates each global and stores it with a `mov r64, r64` or
+    // `mov r64, [r64]` followed by a `mov [rip+x], r64`
+    let mut prev: Option<Instruction> = None;
+    for insn in asm::instructions(mem, ctor).take_while(|x| x.offset < ctor + 0x500) {
+        if let Some(prev) = prev.replace(insn)
+            && prev.rex_w()
+            && prev.is(OpcodeMap::Primary, 0x8B)
+            && prev.len == 3
+            && insn.rex_w()
+            && insn.is(OpcodeMap::Primary, 0x89)
+            && insn.is_rip_relative()
+            && let Some(target) = insn.target
         {
-            let rel_offset = (ptr.add(6) as *const i32).read_unaligned() as isize;
-            let osi_ptr = ptr.offset(rel_offset + 10);
-            globals[found_globals] = osi_ptr as _;
+            globals[found_globals] = module.start.add(target) as _;
             found_globals += 1;
             if found_globals == 9 {
                 break;
@@ -82,33 +140,40 @@ unsafe fn find_osiris_globals(ctor_proc: *const u8) -> Option<OsirisStaticGlobal
         enums: globals[2],
         functions: GamePtr::new(globals[3] as _),
         objects: globals[4],
-        goals: globals[5],
+        goals: GamePtr::new(globals[5] as _),
         adapters: globals[6],
-        databases: globals[7],
-        nodes: globals[8],
+        databases: GamePtr::new(globals[7] as _),
+        nodes: GamePtr::new(globals[8] as _),
     };
 
     Some(osiris_globals)
 }
 
-unsafe fn resolve_real_function_address(ptr: *const u8) -> *const u8 {
-    if *ptr == 0xE9 {
-        let rel_offset = *(ptr.add(1) as *const i32) as isize;
-        return ptr.offset(rel_offset + 5) as _;
+/// Follows incremental linking thunks and the wrappers that check a global
+/// before jumping to the real function. Offsets are relative to `mem`.
+fn resolve_real_function_address(mem: &[u8], func: usize) -> usize {
+    let Some(first) = asm::decode(mem, func) else {
+        return func;
+    };
+    if first.is(OpcodeMap::Primary, 0xE9)
+        && let Some(target) = first.target
+    {
+        return target;
     }
 
-    for i in 0..64 {
-        let p = ptr.add(i);
-        if *p == 0x48
-            && *p.add(1) == 0x83
-            && *p.add(2) == 0x3D
-            && *p.add(6) == 0x00
-            && *p.add(13) == 0xE9
+    // cmp qword ptr [rip+x], imm8 ... jmp real_function
+    let mut insns = asm::instructions(mem, func).take_while(|x| x.offset < func + 64);
+    while let Some(insn) = insns.next() {
+        if insn.rex_w()
+            && insn.is(OpcodeMap::Primary, 0x83)
+            && insn.reg() == Some(7)
+            && insn.is_rip_relative()
+            && let Some(jmp) = insns.by_ref().take(3).find(|x| x.is(OpcodeMap::Primary, 0xE9))
+            && let Some(target) = jmp.target
         {
-            let rel_offset = *(p.add(14) as *const i32) as isize;
-            return p.offset(18 + rel_offset) as _;
+            return target;
         }
     }
 
-    ptr
+    func
 }
@@ -5,11 +5,13 @@ mod binary_mappings;
 mod game_definitions;
 mod globals;
 mod hooks;
+mod log;
 mod menu;
+mod rpc;
 mod version;
 mod wrappers;
 
-use std::panic;
+use std::{panic, path::PathBuf, time::Duration};
 
 use windows::{
     core::w,
@@ -22,7 +24,13 @@ use windows::{
     },
 };
 
-use crate::{binary_mappings::init_static_symbols, globals::Globals};
+use crate::{
+    binary_mappings::init_static_symbols,
+    globals::Globals,
+    menu::search::{export::ExportFormat, DumpStatus},
+};
+
+const DEFAULT_DUMP_TIMEOUT: Duration = Duration::from_secs(600);
 
 #[no_mangle]
 pub extern "system" fn DllMain(_dll: HMODULE, reason: u32, _reserved: &u32) -> BOOL {
@@ -38,7 +46,13 @@ fn main() {
     if let Some(port) =
         std::env::var("BG3_DEBUG_TOOL_PORT").ok().and_then(|x| x.parse::<u16>().ok())
     {
-        Globals::io_set(Some(globals::Io::tcp(format!("127.0.0.1:{port}"))));
+        match globals::Io::tcp(format!("127.0.0.1:{port}")) {
+            Ok(x) => Globals::io_set(Some(x)),
+            Err(x) => {
+                Globals::io_set(Some(globals::Io::stdio()));
+                err!("failed to listen on port {port}, falling back to stdio: {x}");
+            }
+        }
     } else {
         Globals::io_set(Some(globals::Io::stdio()));
     }
@@ -64,6 +78,35 @@ fn main() {
     if let Err(x) = init() {
         panic!("{x}");
     }
+
+    if let Some(dir) = std::env::var_os("BG3_DEBUG_TOOL_DUMP") {
+        start_dump(dir.into());
+    }
+}
+
+/// Dumps the game data on a separate thread and exits the game when done.
+fn start_dump(dir: PathBuf) {
+    let format = std::env::var("BG3_DEBUG_TOOL_DUMP_FORMAT")
+        .ok()
+        .and_then(|x| ExportFormat::from_extension(&x))
+        .unwrap_or(ExportFormat::Json);
+    let timeout = std::env::var("BG3_DEBUG_TOOL_DUMP_TIMEOUT")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .map_or(DEFAULT_DUMP_TIMEOUT, Duration::from_secs);
+
+    std::thread::spawn(move || {
+        info!("Dumping game data to {}", dir.display());
+        let code = match menu::search::dump(&dir, format, timeout) {
+            Ok(DumpStatus::Complete) => 0,
+            Ok(DumpStatus::Partial) => 2,
+            Err(x) => {
+                err!("failed to dump game data: {x}");
+                1
+            }
+        };
+        std::process::exit(code);
+    });
 }
 
 fn init() -> anyhow::Result<()> {
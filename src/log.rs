@@ -0,0 +1,262 @@
+//! Records written by the `info!`, `warn!` and `err!` macros, kept in a ring
+//! buffer for the Log tab and the remote protocol, and mirrored to the
+//! console and a rotating file next to the tool.
+
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+use windows::Win32::{Foundation::SYSTEMTIME, System::SystemInformation::GetLocalTime};
+
+use crate::{globals::Globals, menu::settings::dll_dir};
+
+/// Records past this are dropped, oldest first.
+pub(crate) const DEFAULT_LOG_CAPACITY: usize = 10000;
+const LOG_FILE_NAME: &str = "bg3-debug-tool";
+/// The file is rotated once it grows past this many bytes
+const MAX_LOG_FILE_SIZE: u64 = 4 * 1024 * 1024;
+/// Rotated files are kept as `bg3-debug-tool.1.log` to
+/// `bg3-debug-tool.<n>.log`, the oldest is deleted
+const ROTATED_LOG_FILES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) enum LogLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [Self; 3] = [Self::Info, Self::Warning, Self::Error];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    pub fn color(self) -> Option<[f32; 4]> {
+        match self {
+            LogLevel::Info => None,
+            LogLevel::Warning => Some([1.0, 0.8, 0.3, 1.0]),
+            LogLevel::Error => Some([1.0, 0.3, 0.3, 1.0]),
+        }
+    }
+
+    /// Console colour, the same as the Log tab's
+    fn ansi(self) -> &'static str {
+        match self {
+            LogLevel::Info => "\x1b[1m",
+            LogLevel::Warning => "\x1b[33m",
+            LogLevel::Error => "\x1b[31m",
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Local time a record was written at.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timestamp(SYSTEMTIME);
+
+impl Timestamp {
+    fn now() -> Self {
+        Self(unsafe { GetLocalTime() })
+    }
+
+    fn date(&self) -> String {
+        format!("{}-{:02}-{:02}", self.0.wYear, self.0.wMonth, self.0.wDay)
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = &self.0;
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03}",
+            time.wHour, time.wMinute, time.wSecond, time.wMilliseconds
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LogRecord {
+    /// Number of records written before this one, unique for the session
+    pub id: usize,
+    pub time: Timestamp,
+    pub level: LogLevel,
+    /// Path of the module that wrote it, without the crate name
+    pub module: &'static str,
+    pub message: String,
+}
+
+impl Display for LogRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}] {}: {}", self.time, self.level, self.module, self.message)
+    }
+}
+
+#[derive(Debug)]
+enum LogFile {
+    /// Opened on the first record
+    Closed,
+    Open {
+        file: File,
+        path: PathBuf,
+        size: u64,
+    },
+    /// Not retried, the error is logged once
+    Failed,
+}
+
+/// Ring buffer of the records written since the game started.
+#[derive(Debug)]
+pub(crate) struct Logger {
+    pub records: VecDeque<LogRecord>,
+    pub capacity: usize,
+    /// Records written since the start, including the ones dropped since,
+    /// for readers that only want new ones
+    pub recorded: usize,
+    file: LogFile,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Logger {
+    pub const fn new() -> Self {
+        Self {
+            records: VecDeque::new(),
+            capacity: DEFAULT_LOG_CAPACITY,
+            recorded: 0,
+            file: LogFile::Closed,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Records written after the first `recorded` ones, as many as are still
+    /// in the buffer.
+    pub fn since(&self, recorded: usize) -> impl Iterator<Item = &LogRecord> {
+        let new = self.recorded.saturating_sub(recorded).min(self.records.len());
+        self.records.iter().skip(self.records.len() - new)
+    }
+
+    fn record(&mut self, level: LogLevel, module: &'static str, message: String) {
+        let record = LogRecord {
+            id: self.recorded,
+            time: Timestamp::now(),
+            level,
+            module: module.split_once("::").map_or(module, |x| x.1),
+            message,
+        };
+        let mut failure = None;
+        if let Err(x) = self.write_file(&record) {
+            self.file = LogFile::Failed;
+            failure = Some(format!("failed to write the log file, disabling it: {x}"));
+        }
+
+        while self.records.len() >= self.capacity.max(1) {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+        self.recorded += 1;
+
+        if let Some(message) = failure {
+            self.record(LogLevel::Error, module_path!(), message);
+        }
+    }
+
+    fn write_file(&mut self, record: &LogRecord) -> std::io::Result<()> {
+        if let LogFile::Closed = self.file {
+            let Some(dir) = dll_dir() else {
+                self.file = LogFile::Failed;
+                return Ok(());
+            };
+            let path = dir.join(format!("{LOG_FILE_NAME}.log"));
+            let file = File::options().create(true).append(true).open(&path)?;
+            let size = file.metadata()?.len();
+            self.file = LogFile::Open { file, path, size };
+            self.write_line(&format!("--- {} ---", record.time.date()))?;
+        }
+        self.write_line(&record.to_string())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if let LogFile::Open { size, .. } = self.file
+            && size >= MAX_LOG_FILE_SIZE
+        {
+            self.rotate()?;
+        }
+        let LogFile::Open { file, size, .. } = &mut self.file else {
+            return Ok(());
+        };
+        writeln!(file, "{line}")?;
+        *size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Shifts `bg3-debug-tool.<n>.log` to `<n + 1>` and the current file to
+    /// `1`, then starts a new one.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        // Closed first, Windows doesn't rename open files
+        let LogFile::Open { path, .. } = std::mem::replace(&mut self.file, LogFile::Failed) else {
+            return Ok(());
+        };
+        let rotated = |n: usize| path.with_file_name(format!("{LOG_FILE_NAME}.{n}.log"));
+        let _ = fs::remove_file(rotated(ROTATED_LOG_FILES));
+        for n in (1..ROTATED_LOG_FILES).rev() {
+            let from = rotated(n);
+            if from.exists() {
+                fs::rename(from, rotated(n + 1))?;
+            }
+        }
+        fs::rename(&path, rotated(1))?;
+        self.file = LogFile::Open { file: File::create(&path)?, path, size: 0 };
+        Ok(())
+    }
+}
+
+/// Called by the macros, records `message` and prints it.
+pub(crate) fn log(level: LogLevel, module: &'static str, message: String) {
+    let _ = writeln!(Globals::io_mut(), "{}{message}\x1b[0m", level.ansi());
+    Globals::log().record(level, module, message);
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($tt:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Info, module_path!(), format!($($tt)*))
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($tt:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Warning, module_path!(), format!($($tt)*))
+    };
+}
+
+#[macro_export]
+macro_rules! err {
+    ($($tt:tt)*) => {
+        $crate::log::log($crate::log::LogLevel::Error, module_path!(), format!($($tt)*))
+    };
+}
@@ -1,14 +1,17 @@
 #![windows_subsystem = "windows"]
 
-use std::{fs::File, io::Write, mem, ptr};
+use std::{fs::File, io::Write, mem, path::PathBuf, ptr};
 
 use clap::Parser;
 use windows::{
     core::{s, PCSTR, PSTR},
     Win32::{
-        Foundation::BOOL,
+        Foundation::{CloseHandle, BOOL},
         Security::SECURITY_ATTRIBUTES,
-        System::Threading::{PROCESS_CREATION_FLAGS, PROCESS_INFORMATION, STARTUPINFOA},
+        System::Threading::{
+            GetExitCodeProcess, WaitForSingleObject, INFINITE, PROCESS_CREATION_FLAGS,
+            PROCESS_INFORMATION, STARTUPINFOA,
+        },
     },
 };
 
@@ -17,9 +20,20 @@ struct Args {
     /// Launch the DirectX 11 version of the game
     #[arg(long)]
     dx11: bool,
-    /// TCP port to use for IO
+    /// TCP port serving the remote JSON-RPC protocol, see the README
     #[arg(long)]
     port: Option<u16>,
+    /// Dump every Game Data Explorer category to this directory once the game
+    /// data is loaded, then exit the game
+    #[arg(long, value_name = "DIR")]
+    dump: Option<PathBuf>,
+    /// Format of the dumped files
+    #[arg(long, default_value = "json", value_parser = ["json", "csv", "lsx"])]
+    dump_format: String,
+    /// Seconds to wait for the game data to load before dumping whatever is
+    /// available
+    #[arg(long, default_value_t = 600)]
+    dump_timeout: u64,
 }
 
 fn main() {
@@ -28,6 +42,13 @@ fn main() {
     if let Some(port) = args.port {
         std::env::set_var("BG3_DEBUG_TOOL_PORT", port.to_string());
     }
+    if let Some(dir) = &args.dump {
+        std::fs::create_dir_all(dir).expect("failed to create the dump directory");
+        let dir = std::path::absolute(dir).expect("failed to resolve the dump directory");
+        std::env::set_var("BG3_DEBUG_TOOL_DUMP", dir);
+        std::env::set_var("BG3_DEBUG_TOOL_DUMP_FORMAT", &args.dump_format);
+        std::env::set_var("BG3_DEBUG_TOOL_DUMP_TIMEOUT", args.dump_timeout.to_string());
+    }
 
     File::create("steam_appid.txt")
         .expect("failed to create 'steam_appid.txt'")
@@ -54,6 +75,18 @@ fn main() {
             None,
         );
     };
+
+    // Report the dump result through the exit code
+    if args.dump.is_some() {
+        let mut code = 1;
+        unsafe {
+            WaitForSingleObject(proc_info.hProcess, INFINITE);
+            let _ = GetExitCodeProcess(proc_info.hProcess, &mut code);
+            let _ = CloseHandle(proc_info.hProcess);
+            let _ = CloseHandle(proc_info.hThread);
+        }
+        std::process::exit(code as _);
+    }
 }
 #[link(name = "detours", kind = "static")]
 extern "system" {
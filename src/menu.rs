@@ -2,11 +2,21 @@ use std::ops::DerefMut;
 
 use imgui::{sys::igGetMainViewport, FontConfig, FontGlyphRanges, FontSource, Ui};
 
-use crate::globals::Globals;
+use self::settings::{Hotkeys, Settings};
+use crate::{err, info};
 
 pub(crate) mod backend;
 mod console;
+#[cfg(not(feature = "osi-no-debugger"))]
+mod debugger;
+mod info;
+mod log;
 pub(crate) mod search;
+pub(crate) mod settings;
+mod trace;
+
+/// How often, in seconds, the menu state is checked for changes to save.
+const SETTINGS_SAVE_INTERVAL: f64 = 1.0;
 
 // TODO:
 // - [x] add Osiris function search
@@ -25,16 +35,16 @@ pub(crate) mod search;
 // - [x] figure out Osiris value type names
 // - [x] add more fields to objects
 // - [-] add ability to remove items, spells etc. from the character
-// - [-] add ability to export game data
+// - [x] add ability to export game data
 // - [-] finish other categories
 //   - [x] Osiris functions
 //   - [x] spells
 //   - [x] statuses
 //   - [x] passives
-//   - [ ] reactions
+//   - [x] reactions
 //   - [ ] other template types and ability to spawn them, entities etc.
 // - [ ] finish info tab (components, stats, position)
-// - [ ] add regex search
+// - [x] add regex search
 // - [ ] replace Win32 backend with SDL2
 // - [ ] replace imgui with egui
 // - [ ] ***add icons***
@@ -44,6 +54,15 @@ pub(crate) struct Menu {
     tip_opened: bool,
     search: search::Search,
     console: console::Console,
+    info: info::Info,
+    trace: trace::Trace,
+    #[cfg(not(feature = "osi-no-debugger"))]
+    debugger: debugger::Debugger,
+    log: log::Log,
+    settings: Settings,
+    toggle_menu_key: imgui::Key,
+    toggle_tip_key: imgui::Key,
+    last_settings_check: f64,
 }
 
 unsafe impl Send for Menu {}
@@ -51,16 +70,50 @@ unsafe impl Sync for Menu {}
 
 impl Menu {
     pub fn new() -> Self {
+        let settings = Settings::load();
+        let mut search = search::Search::default();
+        search.apply_settings(&settings.search);
+        let mut console = console::Console::default();
+        console.apply_settings(&settings.console);
+        let mut trace = trace::Trace::default();
+        trace.apply_settings(&settings.trace);
+        let mut log = log::Log::default();
+        log.apply_settings(&settings.log);
+        #[cfg(not(feature = "osi-no-debugger"))]
+        let debugger = {
+            let mut debugger = debugger::Debugger::default();
+            debugger.apply_settings(&settings.debugger);
+            debugger
+        };
+
+        let toggle_menu_key = Hotkeys::key(&settings.hotkeys.toggle_menu).unwrap_or_else(|| {
+            err!("unknown key '{}', using F11", settings.hotkeys.toggle_menu);
+            imgui::Key::F11
+        });
+        let toggle_tip_key = Hotkeys::key(&settings.hotkeys.toggle_tip).unwrap_or_else(|| {
+            err!("unknown key '{}', using F9", settings.hotkeys.toggle_tip);
+            imgui::Key::F9
+        });
+
         Self {
             opened: true,
             tip_opened: true,
-            search: search::Search::default(),
-            console: console::Console::default(),
+            search,
+            console,
+            info: info::Info::default(),
+            trace,
+            #[cfg(not(feature = "osi-no-debugger"))]
+            debugger,
+            log,
+            settings,
+            toggle_menu_key,
+            toggle_tip_key,
+            last_settings_check: 0.0,
         }
     }
 
     pub fn init(ctx: &mut imgui::Context) {
-        ctx.set_ini_filename(None);
+        ctx.set_ini_filename(Settings::imgui_ini_path());
         ctx.set_log_filename(None);
         let io = ctx.io_mut();
 
@@ -78,15 +131,27 @@ impl Menu {
     }
 
     fn render(&mut self, ui: &Ui) {
+        // Osiris calls made from the menu must not hit breakpoints, nothing
+        // would be left to resume them
+        #[cfg(not(feature = "osi-no-debugger"))]
+        crate::wrappers::debugger::mark_ui_thread();
+
+        crate::rpc::game::poll();
+
         let viewport_pos = unsafe { (*igGetMainViewport()).WorkPos };
         let viewport_size = unsafe { (*igGetMainViewport()).WorkSize };
 
-        if ui.is_key_pressed(imgui::Key::F11) {
+        if ui.time() - self.last_settings_check >= SETTINGS_SAVE_INTERVAL {
+            self.last_settings_check = ui.time();
+            self.save_settings();
+        }
+
+        if ui.is_key_pressed(self.toggle_menu_key) {
             self.opened = !self.opened;
         }
 
         if !self.opened {
-            if ui.is_key_pressed(imgui::Key::F9) {
+            if ui.is_key_pressed(self.toggle_tip_key) {
                 self.tip_opened = !self.tip_opened;
             }
             if self.tip_opened {
@@ -96,7 +161,10 @@ impl Menu {
                     .movable(false)
                     .position([0.0, 25.0], imgui::Condition::Always)
                     .build(|| {
-                        ui.text("Press F11 to open the Debug Menu, F9 to hide this text");
+                        ui.text(format!(
+                            "Press {:?} to open the Debug Menu, {:?} to hide this text",
+                            self.toggle_menu_key, self.toggle_tip_key
+                        ));
                     });
             }
             return;
@@ -116,22 +184,53 @@ impl Menu {
                         item.end()
                     }
                     if let Some(item) = ui.tab_item("Console") {
+                        self.console.set_selected(self.search.selected_value());
                         self.console.render(ui);
                         item.end()
                     }
+                    if let Some(item) = ui.tab_item("Trace") {
+                        self.trace.render(ui);
+                        item.end()
+                    }
+                    #[cfg(not(feature = "osi-no-debugger"))]
+                    if let Some(item) = ui.tab_item("Debugger") {
+                        self.debugger.render(ui);
+                        item.end()
+                    }
                     if let Some(item) = ui.tab_item("Info") {
+                        self.info.render(ui);
                         item.end()
                     }
                     if let Some(item) = ui.tab_item("Log") {
-                        ui.input_text_multiline("Log", &mut Globals::log(), [-1.0, -1.0])
-                            .read_only(true)
-                            .build();
+                        self.log.render(ui);
                         item.end()
                     }
                     tab_bar.end();
                 }
             });
     }
+
+    /// Writes the settings file if anything changed since it was last saved.
+    fn save_settings(&mut self) {
+        let settings = Settings {
+            hotkeys: self.settings.hotkeys.clone(),
+            search: self.search.settings(),
+            console: self.console.settings(),
+            trace: self.trace.settings(),
+            #[cfg(not(feature = "osi-no-debugger"))]
+            debugger: self.debugger.settings(),
+            #[cfg(feature = "osi-no-debugger")]
+            debugger: self.settings.debugger.clone(),
+            log: self.log.settings(),
+        };
+        if settings == self.settings {
+            return;
+        }
+        if let Err(x) = settings.save() {
+            err!("failed to save settings: {x}");
+        }
+        self.settings = settings;
+    }
 }
 
 impl ImGuiMenu<ash::Device> for Menu {
@@ -154,6 +253,19 @@ impl ImGuiMenu<()> for Menu {
     }
 }
 
+/// Writes `contents` next to the game executable.
+fn export(name: &str, contents: anyhow::Result<String>) {
+    let res = contents.and_then(|contents| {
+        let path = std::env::current_exe()?.with_file_name(name);
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    });
+    match res {
+        Ok(path) => info!("Exported {}", path.display()),
+        Err(x) => err!("failed to export {name}: {x}"),
+    }
+}
+
 pub(crate) trait ImGuiMenu<InitParam> {
     fn init(&mut self, _ctx: &mut imgui::Context, _params: &mut InitParam) {}
     fn pre_render(&mut self, _ctx: &mut imgui::Context) {}
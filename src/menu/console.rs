@@ -1,6 +1,32 @@
 use imgui::{HistoryDirection, InputTextCallback, InputTextCallbackHandler, TextCallbackData, Ui};
 
-use crate::wrappers::osiris::FunctionCall;
+use super::{
+    search::completion::Completions,
+    settings::{dll_dir, ConsoleSettings},
+};
+use crate::wrappers::{osiris::Value, script::ScriptEnv};
+
+const CONSOLE_HELP: &str = "\
+Calls or queries an Osiris function, the overload is picked by the argument types
+    ApplyStatus($host, BURNING, 6.0, 1, None)
+
+Arguments are numbers, \"quoted\" or bare strings and GUIDs, None, and variables
+    S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c    -5    \"Some text\"
+    $host        the host character
+    $selected    the item, spell, etc. selected in the Game Data Explorer
+    $last        the result of the last query
+
+Statements are separated by ';', query results can be stored, tested and looped over
+    let $gold = GetGold($host); if IsInCombat($host) { ... } else { ... }
+    for $member in DB_Players { TemplateAddTo($selected, $member, 10, 1) }
+    for $status in [BURNING, WET] { ApplyStatus($host, $status, 6.0, 1, None) }
+
+Macros are kept until the game is closed, .osiscript files next to the tool can be included
+    macro give($item, $amount) { for $p in DB_Players { TemplateAddTo($item, $p, $amount, 1) } }
+    include \"potions.osiscript\"
+
+Tab completes function names, variables and argument values, the signature of
+the function being called is shown below the input";
 
 #[derive(Debug)]
 pub(crate) struct Console {
@@ -8,6 +34,11 @@ pub(crate) struct Console {
     output: String,
     history: History,
     reclaim_focus: bool,
+    env: ScriptEnv,
+    /// Loaded on first use
+    completions: Option<Completions>,
+    /// Byte offset in `text`, updated by the input callback
+    cursor: usize,
 }
 
 impl Default for Console {
@@ -17,6 +48,9 @@ impl Default for Console {
             output: String::new(),
             history: History::new(100),
             reclaim_focus: true,
+            env: ScriptEnv { dir: dll_dir(), ..Default::default() },
+            completions: None,
+            cursor: 0,
         }
     }
 }
@@ -24,15 +58,33 @@ impl Default for Console {
 impl Console {
     pub fn render(&mut self, ui: &Ui) {
         ui.text("Console");
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip_text(CONSOLE_HELP);
+        }
         ui.text(">>");
         ui.same_line();
         if self.reclaim_focus {
             ui.set_keyboard_focus_here();
             self.reclaim_focus = false;
         }
+        let callbacks = InputCallbacks {
+            history: &mut self.history,
+            completions: &mut self.completions,
+            env: &self.env,
+            output: &mut self.output,
+            cursor: &mut self.cursor,
+        };
         if ui
             .input_text("##input", &mut self.text)
-            .callback(InputTextCallback::HISTORY, &mut self.history)
+            .callback(
+                InputTextCallback::HISTORY
+                    | InputTextCallback::COMPLETION
+                    | InputTextCallback::ALWAYS,
+                callbacks,
+            )
+            .hint("Tab to complete")
             .enter_returns_true(true)
             .build()
         {
@@ -43,47 +95,104 @@ impl Console {
         if ui.button("Run") {
             self.run();
         }
+        if self.completions.is_none() && !self.text.is_empty() {
+            self.completions = Some(Completions::load());
+        }
+        if let Some(hint) = self
+            .completions
+            .as_ref()
+            .and_then(|x| x.hint(self.text.get(..self.cursor).unwrap_or(&self.text), &self.env))
+        {
+            ui.text_disabled(hint);
+        }
         if ui.button("Clear") {
             self.output.clear();
         }
         ui.input_text_multiline("##output", &mut self.output, [-1.0, -1.0]).read_only(true).build();
     }
 
+    pub fn settings(&self) -> ConsoleSettings {
+        ConsoleSettings { history: self.history.buf.iter().map(|x| x.to_string()).collect() }
+    }
+
+    pub fn apply_settings(&mut self, settings: &ConsoleSettings) {
+        self.history.buf =
+            settings.history.iter().take(self.history.size).map(|x| x.as_str().into()).collect();
+    }
+
+    /// Binds `$selected`, unset if nothing is selected.
+    pub fn set_selected(&mut self, value: Option<Value>) {
+        match value {
+            Some(x) => self.env.variables.insert("selected".into(), x),
+            None => self.env.variables.remove("selected"),
+        };
+    }
+
     pub fn run(&mut self) {
         self.output.push_str(&format!(">> {}\n", self.text));
-        let call = syn::parse_str::<FunctionCall>(&self.text);
+        let res = self.env.run(&self.text, &mut self.output);
         self.history.insert(self.text.clone());
         self.text.clear();
-        match call {
-            Ok(x) => match x.call() {
-                Ok(Some(x)) => self.output.push_str(&x.to_string()),
-                Ok(None) => (),
-                Err(x) => self.output.push_str(&x.to_string()),
-            },
-            Err(x) => self.output.push_str(&x.to_string()),
+        if let Err(x) = res {
+            self.output.push_str(&x.to_string());
+            self.output.push('\n');
         }
-        self.output.push('\n');
         self.reclaim_focus = true;
     }
 }
 
-impl InputTextCallbackHandler for &mut History {
+struct InputCallbacks<'a> {
+    history: &'a mut History,
+    completions: &'a mut Option<Completions>,
+    env: &'a ScriptEnv,
+    output: &'a mut String,
+    cursor: &'a mut usize,
+}
+
+impl InputTextCallbackHandler for InputCallbacks<'_> {
     fn on_history(&mut self, dir: HistoryDirection, mut data: TextCallbackData) {
         match dir {
             HistoryDirection::Up => {
-                if let Some(string) = self.prev(data.str()) {
+                if let Some(string) = self.history.prev(data.str()) {
                     data.clear();
                     data.push_str(string);
                 }
             }
             HistoryDirection::Down => {
-                if let Some(string) = self.next() {
+                if let Some(string) = self.history.next() {
                     data.clear();
                     data.push_str(string);
                 }
             }
         }
     }
+
+    fn on_completion(&mut self, mut data: TextCallbackData) {
+        if self.completions.as_ref().is_none_or(Completions::is_stale) {
+            *self.completions = Some(Completions::load());
+        }
+        let Some(completions) = self.completions.as_ref() else {
+            return;
+        };
+        let cursor = data.cursor_pos();
+        let Some(completion) =
+            data.str().get(..cursor).and_then(|x| completions.complete(x, self.env))
+        else {
+            return;
+        };
+
+        for line in &completion.listed {
+            self.output.push_str(line);
+            self.output.push('\n');
+        }
+        let replaced = data.str()[completion.start..cursor].chars().count();
+        data.remove_chars(completion.start, replaced);
+        data.insert_chars(completion.start, &completion.text);
+    }
+
+    fn on_always(&mut self, data: TextCallbackData) {
+        *self.cursor = data.cursor_pos();
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,175 @@
+use imgui::{TableColumnSetup, TableFlags, Ui};
+
+use super::settings::{BreakpointSettings, DebuggerSettings};
+use crate::{
+    globals::Globals,
+    wrappers::debugger::{self, Breakpoint},
+};
+
+const CONDITION_HELP: &str = "Comma separated argument values, compared in order.\n'_' or an \
+                              empty value matches anything, '!' negates.\ne.g. '_, \
+                              S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c'";
+
+#[derive(Default)]
+pub(crate) struct Debugger {
+    name: String,
+    condition: String,
+}
+
+impl Debugger {
+    pub fn render(&mut self, ui: &Ui) {
+        let mut debugger = Globals::debugger();
+        self.draw(ui, &mut debugger);
+        debugger.update_armed();
+    }
+
+    fn draw(&mut self, ui: &Ui, debugger: &mut debugger::Debugger) {
+        if debugger.is_paused() {
+            let Some(paused) = &mut debugger.paused else {
+                return;
+            };
+            ui.text_colored(
+                [1.0, 0.8, 0.3, 1.0],
+                format!("Paused in {} {}", paused.kind, paused.name),
+            );
+            ui.text("Arguments");
+            if paused.args.is_empty() {
+                ui.text_disabled("None");
+            } else if let Some(_tbl) =
+                ui.begin_table_with_flags("debugger-args", 3, TableFlags::RESIZABLE)
+            {
+                for name in ["#", "Type", "Value"] {
+                    ui.table_setup_column_with(TableColumnSetup::new(name));
+                }
+                ui.table_headers_row();
+                for (i, arg) in paused.args.iter_mut().enumerate() {
+                    ui.table_next_row();
+                    ui.table_next_column();
+                    ui.text(i.to_string());
+                    ui.table_next_column();
+                    ui.text(format!("{:?}", arg.r#type));
+                    ui.table_next_column();
+                    ui.set_next_item_width(-1.0);
+                    ui.input_text(format!("##debugger-arg-{i}"), &mut arg.text).build();
+                    if arg.text != arg.original && ui.is_item_hovered() {
+                        ui.tooltip_text(format!("Was '{}'", arg.original));
+                    }
+                }
+            }
+
+            ui.text("Call stack");
+            for (kind, name, args) in paused.stack.iter().rev() {
+                ui.bullet_text(format!("{kind} {name}({args})"));
+            }
+
+            if ui.button("Continue") {
+                debugger.resume(false);
+            }
+            ui.same_line();
+            if ui.button("Step") {
+                debugger.resume(true);
+            }
+            ui.same_line();
+            ui.text_disabled("(?)");
+            if ui.is_item_hovered() {
+                ui.tooltip_text(
+                    "The story is paused until resumed, edited arguments are passed to the \
+                     function.\nStep pauses again on the next event, call or query.",
+                );
+            }
+        } else if debugger.step {
+            ui.text("Waiting for the next event, call or query...");
+            ui.same_line();
+            if ui.button("Cancel") {
+                debugger.step = false;
+            }
+        } else {
+            ui.text("Running");
+            ui.same_line();
+            if ui.button("Break on next function") {
+                debugger.step = true;
+            }
+        }
+
+        ui.separator();
+        ui.text("Breakpoints");
+        ui.input_text("Function", &mut self.name).hint("e.g. ApplyStatus").build();
+        ui.input_text("Condition", &mut self.condition).build();
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip_text(CONDITION_HELP);
+        }
+        if ui.button("Add Breakpoint") && !self.name.trim().is_empty() {
+            debugger.breakpoints.push(Breakpoint {
+                name: self.name.trim().into(),
+                condition: self.condition.clone(),
+                enabled: true,
+                hits: 0,
+            });
+            self.name.clear();
+            self.condition.clear();
+        }
+
+        let Some(_tbl) = ui.begin_table_with_flags(
+            "debugger-breakpoints",
+            5,
+            TableFlags::RESIZABLE | TableFlags::ROW_BG,
+        ) else {
+            return;
+        };
+        for name in ["Enabled", "Function", "Condition", "Hits", ""] {
+            ui.table_setup_column_with(TableColumnSetup::new(name));
+        }
+        ui.table_headers_row();
+
+        let mut removed = None;
+        for (i, bp) in debugger.breakpoints.iter_mut().enumerate() {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.checkbox(format!("##bp-enabled-{i}"), &mut bp.enabled);
+            ui.table_next_column();
+            ui.text(&bp.name);
+            ui.table_next_column();
+            ui.text(&bp.condition);
+            ui.table_next_column();
+            ui.text(bp.hits.to_string());
+            ui.table_next_column();
+            if ui.small_button(format!("Remove##bp-remove-{i}")) {
+                removed = Some(i);
+            }
+        }
+        if let Some(i) = removed {
+            debugger.breakpoints.remove(i);
+        }
+    }
+
+    pub fn settings(&self) -> DebuggerSettings {
+        DebuggerSettings {
+            breakpoints: Globals::debugger()
+                .breakpoints
+                .iter()
+                .map(|x| BreakpointSettings {
+                    name: x.name.clone(),
+                    condition: x.condition.clone(),
+                    enabled: x.enabled,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &DebuggerSettings) {
+        let mut debugger = Globals::debugger();
+        debugger.breakpoints = settings
+            .breakpoints
+            .iter()
+            .map(|x| Breakpoint {
+                name: x.name.clone(),
+                condition: x.condition.clone(),
+                enabled: x.enabled,
+                hits: 0,
+            })
+            .collect();
+        debugger.update_armed();
+    }
+}
@@ -0,0 +1,90 @@
+use imgui::{TableColumnSetup, TableFlags, Ui};
+
+use super::export;
+use crate::{
+    binary_mappings::{SymbolReport, SymbolStatus},
+    globals::Globals,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct Info {
+    filter: String,
+}
+
+impl Info {
+    pub fn render(&mut self, ui: &Ui) {
+        let report = Globals::symbol_report();
+
+        ui.text(format!(
+            "Static symbols: {} resolved, {} unverified, {} missing, {} unmapped",
+            report.count(SymbolStatus::Resolved),
+            report.count(SymbolStatus::Unverified),
+            report.count(SymbolStatus::Missing),
+            report.count(SymbolStatus::Unmapped),
+        ));
+        if ui.button("Export JSON") {
+            export("symbol_report.json", report.to_json());
+        }
+        ui.same_line();
+        if ui.button("Export CSV") {
+            export("symbol_report.csv", Ok(report.to_csv()));
+        }
+        ui.same_line();
+        ui.input_text("Filter", &mut self.filter).build();
+
+        self.draw_table(ui, report);
+    }
+
+    fn draw_table(&self, ui: &Ui, report: &SymbolReport) {
+        let Some(_tbl) = ui.begin_table_with_sizing(
+            "symbols-tbl",
+            6,
+            TableFlags::SCROLL_Y | TableFlags::RESIZABLE | TableFlags::ROW_BG,
+            [0.0, -1.0],
+            0.0,
+        ) else {
+            return;
+        };
+
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["Symbol", "Address", "RVA", "Mapping", "Pattern", "Status"] {
+            ui.table_setup_column_with(TableColumnSetup::new(name));
+        }
+        ui.table_headers_row();
+
+        let filter = self.filter.to_lowercase();
+        let hex = |x: Option<usize>| x.map(|x| format!("{x:#X}")).unwrap_or_else(|| "-".into());
+        for entry in report.entries.iter().filter(|x| {
+            x.symbol.to_lowercase().contains(&filter)
+                || x.mapping.as_ref().is_some_and(|x| x.to_lowercase().contains(&filter))
+                || x.status.as_str().to_lowercase().contains(&filter)
+        }) {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text(entry.symbol);
+            ui.table_next_column();
+            ui.text(hex(entry.address));
+            ui.table_next_column();
+            ui.text(hex(entry.rva));
+            ui.table_next_column();
+            ui.text(entry.mapping.as_deref().unwrap_or("-"));
+            ui.table_next_column();
+            let pattern = entry.pattern.as_deref().unwrap_or("-");
+            ui.text(pattern);
+            if ui.is_item_hovered() && entry.pattern.is_some() {
+                ui.tooltip_text(pattern);
+            }
+            ui.table_next_column();
+            let color = match entry.status {
+                SymbolStatus::Resolved => [0.4, 0.9, 0.4, 1.0],
+                SymbolStatus::Unverified => [0.9, 0.9, 0.3, 1.0],
+                SymbolStatus::Missing => [0.9, 0.3, 0.3, 1.0],
+                SymbolStatus::Unmapped => [0.6, 0.6, 0.6, 1.0],
+            };
+            ui.text_colored(color, entry.status.as_str());
+            if ui.is_item_hovered() && !entry.errors.is_empty() {
+                ui.tooltip_text(entry.errors.join("\n"));
+            }
+        }
+    }
+}
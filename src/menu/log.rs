@@ -0,0 +1,206 @@
+use std::collections::{BTreeSet, HashSet};
+
+use imgui::{Key, ListClipper, TableColumnSetup, TableFlags, Ui, WindowFocusedFlags};
+
+use super::settings::LogSettings;
+use crate::{
+    globals::Globals,
+    log::{LogLevel, LogRecord, DEFAULT_LOG_CAPACITY},
+};
+
+pub(crate) struct Log {
+    /// Records below it are hidden
+    level: LogLevel,
+    filter: String,
+    capacity: i32,
+    auto_scroll: bool,
+    /// Ids of the selected records
+    selected: BTreeSet<usize>,
+    /// Id of the last record clicked, where Shift+click selects from
+    anchor: Option<usize>,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            filter: String::new(),
+            capacity: DEFAULT_LOG_CAPACITY as _,
+            auto_scroll: true,
+            selected: BTreeSet::new(),
+            anchor: None,
+        }
+    }
+}
+
+impl Log {
+    pub fn render(&mut self, ui: &Ui) {
+        // Ids of the shown records, the log is only locked while it's read
+        // so that drawing doesn't block the threads writing to it.
+        let shown = {
+            let mut logger = Globals::log();
+
+            if ui.button("Clear") {
+                logger.clear();
+                self.selected.clear();
+            }
+            ui.same_line();
+            ui.checkbox("Auto-scroll", &mut self.auto_scroll);
+            ui.same_line();
+            ui.text(format!("{} of {} records", logger.records.len(), logger.capacity));
+
+            ui.set_next_item_width(ui.current_font_size() * 8.0);
+            if ui.input_int("Capacity", &mut self.capacity).build() {
+                self.capacity = self.capacity.max(1);
+                logger.capacity = self.capacity as _;
+            }
+            ui.same_line();
+            ui.set_next_item_width(ui.current_font_size() * 8.0);
+            let mut level = LogLevel::ALL.iter().position(|x| *x == self.level).unwrap_or_default();
+            if ui.combo("Level", &mut level, &LogLevel::ALL, |x| x.name().into()) {
+                self.level = LogLevel::ALL[level];
+            }
+            ui.input_text("Filter", &mut self.filter).build();
+
+            let filter = self.filter.to_lowercase();
+            logger
+                .records
+                .iter()
+                .filter(|x| x.level >= self.level)
+                .filter(|x| {
+                    filter.is_empty()
+                        || x.message.to_lowercase().contains(&filter)
+                        || x.module.to_lowercase().contains(&filter)
+                })
+                .map(|x| x.id)
+                .collect::<Vec<_>>()
+        };
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Matches the message and module by substring, case insensitive.\nClick to select \
+                 a record, Ctrl+click and Shift+click to select more, Ctrl+C to copy them.",
+            );
+        }
+
+        let shown_ids = shown.iter().copied().collect::<HashSet<_>>();
+        self.selected.retain(|id| shown_ids.contains(id));
+
+        ui.disabled(self.selected.is_empty(), || {
+            if ui.button("Copy selection") {
+                self.copy(ui);
+            }
+        });
+        ui.same_line();
+        ui.text(format!("{} selected", self.selected.len()));
+
+        let focused = ui.is_window_focused_with_flags(WindowFocusedFlags::ROOT_AND_CHILD_WINDOWS);
+        if focused && ui.io().key_ctrl && ui.is_key_pressed(Key::C) {
+            self.copy(ui);
+        }
+
+        let Some(_tbl) = ui.begin_table_with_sizing(
+            "log-tbl",
+            4,
+            TableFlags::SCROLL_Y | TableFlags::RESIZABLE | TableFlags::ROW_BG,
+            [0.0, -1.0],
+            0.0,
+        ) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["Time", "Level", "Module", "Message"] {
+            ui.table_setup_column_with(TableColumnSetup::new(name));
+        }
+        ui.table_headers_row();
+
+        let mut clipper = ListClipper::new(shown.len() as _).begin(ui);
+        while clipper.step() {
+            let range = clipper.display_start() as usize..clipper.display_end() as usize;
+            for record in records(&shown[range]) {
+                ui.table_next_row();
+                ui.table_next_column();
+                if ui
+                    .selectable_config(format!("{}##log{}", record.time, record.id))
+                    .span_all_columns(true)
+                    .selected(self.selected.contains(&record.id))
+                    .build()
+                {
+                    self.select(ui, &shown, record.id);
+                }
+
+                let color = record.level.color();
+                let text = |text: &str| match color {
+                    Some(color) => ui.text_colored(color, text),
+                    None => ui.text(text),
+                };
+                ui.table_next_column();
+                text(record.level.name());
+                ui.table_next_column();
+                text(record.module);
+                ui.table_next_column();
+                text(&record.message);
+            }
+        }
+        if self.auto_scroll && ui.scroll_y() >= ui.scroll_max_y() {
+            ui.set_scroll_here_y_with_ratio(1.0);
+        }
+    }
+
+    /// Click selects `id` alone, Ctrl+click toggles it and Shift+click
+    /// selects the shown records from the last one clicked.
+    fn select(&mut self, ui: &Ui, shown: &[usize], id: usize) {
+        let io = ui.io();
+        if io.key_shift
+            && let Some(anchor) = self.anchor
+        {
+            let (from, to) = (anchor.min(id), anchor.max(id));
+            if !io.key_ctrl {
+                self.selected.clear();
+            }
+            self.selected.extend(shown.iter().copied().filter(|x| (from..=to).contains(x)));
+            return;
+        }
+
+        if io.key_ctrl {
+            if !self.selected.remove(&id) {
+                self.selected.insert(id);
+            }
+        } else {
+            self.selected.clear();
+            self.selected.insert(id);
+        }
+        self.anchor = Some(id);
+    }
+
+    fn copy(&self, ui: &Ui) {
+        let ids = self.selected.iter().copied().collect::<Vec<_>>();
+        let text = records(&ids).iter().map(|x| x.to_string()).collect::<Vec<_>>().join("\n");
+        if !text.is_empty() {
+            ui.set_clipboard_text(text);
+        }
+    }
+
+    pub fn settings(&self) -> LogSettings {
+        LogSettings { level: self.level, filter: self.filter.clone(), capacity: self.capacity as _ }
+    }
+
+    pub fn apply_settings(&mut self, settings: &LogSettings) {
+        self.level = settings.level;
+        self.filter.clone_from(&settings.filter);
+        self.capacity = settings.capacity.max(1) as _;
+        Globals::log().capacity = self.capacity as _;
+    }
+}
+
+/// Copies of the records with the sorted `ids` that are still in the log.
+fn records(ids: &[usize]) -> Vec<LogRecord> {
+    let logger = Globals::log();
+    ids.iter()
+        .filter_map(|id| {
+            let i = logger.records.binary_search_by_key(id, |x| x.id).ok()?;
+            Some(logger.records[i].clone())
+        })
+        .collect()
+}
@@ -1,36 +1,102 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    path::Path,
+    time::{Duration, Instant},
+};
 
+use anyhow::anyhow;
 use imgui::{TableFlags, Ui};
 
 use self::{
+    databases::DatabaseCategory,
+    export::{export_item, ExportCtx, ExportFormat, ExportValue},
     functions::FunctionCategory,
+    goals::GoalCategory,
+    interrupts::InterruptCategory,
     passives::PassiveCategory,
+    query::Query,
     spells::SpellCategory,
+    stats::StatsCategory,
     statuses::StatusCategory,
     table::ObjectTable,
-    templates::{GameObjectTemplateCategory, ItemCategory, SceneryCategory},
+    templates::{
+        CharacterCategory, GameObjectTemplateCategory, ItemCategory, LevelTemplateCategory,
+        LightCategory, PrefabCategory, ProjectileCategory, SceneryCategory, SurfaceCategory,
+        TriggerCategory,
+    },
 };
-use crate::{game_definitions as gd, globals::Globals};
+use super::settings::SearchSettings;
+use crate::{game_definitions as gd, globals::Globals, info, version, warn, wrappers::osiris};
 
+pub(crate) mod completion;
+mod databases;
+pub(crate) mod export;
 mod functions;
+mod goals;
+mod interrupts;
 mod osiris_helpers;
 mod passives;
+pub(crate) mod query;
 mod spells;
+mod stats;
 mod statuses;
 pub(crate) mod table;
 pub(crate) mod table_value;
 mod templates;
 
+const CATEGORIES: [&str; 18] = [
+    "Items",
+    "Characters",
+    "Spells",
+    "Statuses",
+    "Passives",
+    "Interrupts",
+    "Stats",
+    "Osiris Functions",
+    "Osiris Databases",
+    "Osiris Goals",
+    "Scenery Templates",
+    "Triggers",
+    "Projectiles",
+    "Surfaces",
+    "Prefabs",
+    "Lights",
+    "Level Templates",
+    "Templates",
+];
+
+const QUERY_HELP: &str = "\
+Terms are matched against the fields selected in Search Fields
+    fire \"fire bolt\" /^fire.*bolt$/
+
+Search a specific field, nested fields are separated by '.'
+    display_name:fire    internal_name=Projectile_FireBolt    internal_name:/^target_/
+    spell.level>=3    spell.level<2
+
+Combine terms with AND (or a space), OR, NOT (or '-') and parentheses
+    spell.level=3 (spell.damage_type:fire OR spell.damage_type:cold)";
+
 macro_rules! choose_category {
     ($ident:ident, $($tt:tt)*) => {
         match $ident.cur_category {
             0 => $ident.items.$($tt)*,
-            1 => $ident.spells.$($tt)*,
-            2 => $ident.statuses.$($tt)*,
-            3 => $ident.passives.$($tt)*,
-            4 => $ident.functions.$($tt)*,
-            5 => $ident.scenery.$($tt)*,
-            6 => $ident.templates.$($tt)*,
+            1 => $ident.characters.$($tt)*,
+            2 => $ident.spells.$($tt)*,
+            3 => $ident.statuses.$($tt)*,
+            4 => $ident.passives.$($tt)*,
+            5 => $ident.interrupts.$($tt)*,
+            6 => $ident.stats.$($tt)*,
+            7 => $ident.functions.$($tt)*,
+            8 => $ident.databases.$($tt)*,
+            9 => $ident.goals.$($tt)*,
+            10 => $ident.scenery.$($tt)*,
+            11 => $ident.triggers.$($tt)*,
+            12 => $ident.projectiles.$($tt)*,
+            13 => $ident.surfaces.$($tt)*,
+            14 => $ident.prefabs.$($tt)*,
+            15 => $ident.lights.$($tt)*,
+            16 => $ident.levels.$($tt)*,
+            17 => $ident.templates.$($tt)*,
             _ => unreachable!(),
         }
     };
@@ -39,15 +105,27 @@ macro_rules! choose_category {
 pub(crate) struct Search {
     reclaim_focus: bool,
     search_failed: bool,
+    query_error: Option<String>,
     cur_category: usize,
     text: String,
     options: Options,
     items: ObjectTable<ItemCategory>,
+    characters: ObjectTable<CharacterCategory>,
     spells: ObjectTable<SpellCategory>,
     statuses: ObjectTable<StatusCategory>,
     passives: ObjectTable<PassiveCategory>,
+    interrupts: ObjectTable<InterruptCategory>,
+    stats: ObjectTable<StatsCategory>,
     functions: ObjectTable<FunctionCategory>,
+    databases: ObjectTable<DatabaseCategory>,
+    goals: ObjectTable<GoalCategory>,
     scenery: ObjectTable<SceneryCategory>,
+    triggers: ObjectTable<TriggerCategory>,
+    projectiles: ObjectTable<ProjectileCategory>,
+    surfaces: ObjectTable<SurfaceCategory>,
+    prefabs: ObjectTable<PrefabCategory>,
+    lights: ObjectTable<LightCategory>,
+    levels: ObjectTable<LevelTemplateCategory>,
     templates: ObjectTable<GameObjectTemplateCategory>,
 }
 
@@ -56,15 +134,27 @@ impl Default for Search {
         Self {
             reclaim_focus: true,
             search_failed: false,
+            query_error: None,
             cur_category: 0,
             text: String::new(),
             options: Options::default(),
             items: ObjectTable::default(),
+            characters: ObjectTable::default(),
             spells: ObjectTable::default(),
             statuses: ObjectTable::default(),
             passives: ObjectTable::default(),
+            interrupts: ObjectTable::default(),
+            stats: ObjectTable::default(),
             functions: ObjectTable::default(),
+            databases: ObjectTable::default(),
+            goals: ObjectTable::default(),
             scenery: ObjectTable::default(),
+            triggers: ObjectTable::default(),
+            projectiles: ObjectTable::default(),
+            surfaces: ObjectTable::default(),
+            prefabs: ObjectTable::default(),
+            lights: ObjectTable::default(),
+            levels: ObjectTable::default(),
             templates: ObjectTable::default(),
         }
     }
@@ -79,20 +169,9 @@ impl Search {
         }
 
         ui.text("Object Category");
-        if ui.combo(
-            "##object-category-combo",
-            &mut self.cur_category,
-            &[
-                "Items",
-                "Spells",
-                "Statuses",
-                "Passives",
-                "Osiris Functions",
-                "Scenery Templates",
-                "Templates",
-            ],
-            |x| Cow::from(*x),
-        ) && self.text.is_empty()
+        if ui.combo("##object-category-combo", &mut self.cur_category, &CATEGORIES, |x| {
+            Cow::from(*x)
+        }) && self.text.is_empty()
             && cur_category!(items.len() == 0)
         {
             self.search();
@@ -127,6 +206,24 @@ impl Search {
         if ui.button("Search") {
             self.search();
         }
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip_text(QUERY_HELP);
+        }
+        if let Some(x) = &self.query_error {
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], format!("Invalid query, {x}"));
+        }
+
+        if !self.search_failed && cur_category!(items.len() != 0) {
+            ui.text("Export results as");
+            for format in ExportFormat::ALL {
+                ui.same_line();
+                if ui.button(format.name()) {
+                    self.export(format);
+                }
+            }
+        }
 
         if self.search_failed {
             ui.text("Failed to load items, try loading a save");
@@ -140,6 +237,102 @@ impl Search {
         }
     }
 
+    pub fn selected_value(&self) -> Option<osiris::Value> {
+        choose_category!(self, selected_value())
+    }
+
+    pub fn settings(&self) -> SearchSettings {
+        SearchSettings {
+            category: CATEGORIES[self.cur_category].into(),
+            case_sensitive: self.options.case_sensitive,
+            tables: [
+                self.items.settings(),
+                self.characters.settings(),
+                self.spells.settings(),
+                self.statuses.settings(),
+                self.passives.settings(),
+                self.interrupts.settings(),
+                self.stats.settings(),
+                self.functions.settings(),
+                self.databases.settings(),
+                self.goals.settings(),
+                self.scenery.settings(),
+                self.triggers.settings(),
+                self.projectiles.settings(),
+                self.surfaces.settings(),
+                self.prefabs.settings(),
+                self.lights.settings(),
+                self.levels.settings(),
+                self.templates.settings(),
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| (CATEGORIES[i].into(), x))
+            .collect(),
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &SearchSettings) {
+        if let Some(i) = CATEGORIES.iter().position(|x| *x == settings.category) {
+            self.cur_category = i;
+        }
+        self.options.case_sensitive = settings.case_sensitive;
+
+        for (i, name) in CATEGORIES.iter().enumerate() {
+            let Some(table) = settings.tables.get(*name) else {
+                continue;
+            };
+            match i {
+                0 => self.items.apply_settings(table),
+                1 => self.characters.apply_settings(table),
+                2 => self.spells.apply_settings(table),
+                3 => self.statuses.apply_settings(table),
+                4 => self.passives.apply_settings(table),
+                5 => self.interrupts.apply_settings(table),
+                6 => self.stats.apply_settings(table),
+                7 => self.functions.apply_settings(table),
+                8 => self.databases.apply_settings(table),
+                9 => self.goals.apply_settings(table),
+                10 => self.scenery.apply_settings(table),
+                11 => self.triggers.apply_settings(table),
+                12 => self.projectiles.apply_settings(table),
+                13 => self.surfaces.apply_settings(table),
+                14 => self.prefabs.apply_settings(table),
+                15 => self.lights.apply_settings(table),
+                16 => self.levels.apply_settings(table),
+                17 => self.templates.apply_settings(table),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// A search on the category named `category`, ignoring case and spaces.
+    fn with_category(category: &str) -> anyhow::Result<Self> {
+        let normalize = |x: &str| x.replace(' ', "").to_lowercase();
+        let cur_category =
+            CATEGORIES.iter().position(|x| normalize(x) == normalize(category)).ok_or_else(
+                || anyhow!("unknown category '{category}', expected one of {CATEGORIES:?}"),
+            )?;
+        Ok(Self { cur_category, ..Default::default() })
+    }
+
+    /// Exports the current search results, the file is named after the
+    /// category and the game version so exports from different patches can be
+    /// diffed.
+    fn export(&self, format: ExportFormat) {
+        macro_rules! cur_category {
+            ($($tt:tt)*) => {
+                choose_category!(self, $($tt)*)
+            };
+        }
+
+        let category = CATEGORIES[self.cur_category];
+        super::export(
+            &export_file_name(category, format),
+            cur_category!(export(&category.replace(' ', ""), format)),
+        );
+    }
+
     fn search(&mut self) {
         macro_rules! cur_category {
             ($($tt:tt)*) => {
@@ -147,8 +340,16 @@ impl Search {
             };
         }
 
-        self.search_failed = cur_category!(search(&self.text, &self.options)).is_none();
         self.reclaim_focus = true;
+        let query = match Query::parse(&self.text, &self.options, cur_category!(columns.as_ref())) {
+            Ok(x) => x,
+            Err(x) => {
+                self.query_error = Some(x.to_string());
+                return;
+            }
+        };
+        self.query_error = None;
+        self.search_failed = cur_category!(search(&query)).is_none();
     }
 }
 
@@ -157,9 +358,133 @@ pub(crate) struct Options {
     case_sensitive: bool,
 }
 
-pub(crate) fn templates() -> impl Iterator<Item = gd::Template<'static>> {
-    let template_manager = *Globals::static_symbols().ls__GlobalTemplateManager.unwrap();
+fn export_file_name(category: &str, format: ExportFormat) -> String {
+    let mut name = category.to_lowercase().replace(' ', "_");
+    if let Ok(version) = version::game_version() {
+        name.push_str(&format!("_{version}"));
+    }
+    format!("{name}.{}", format.extension())
+}
+
+/// Categories [`dump`] waits for, the others aren't necessarily loaded at the
+/// main menu, e.g. the Osiris ones need a save.
+const DUMP_REQUIRED: [&str; 6] =
+    ["Items", "Spells", "Statuses", "Passives", "Scenery Templates", "Templates"];
+/// Requested categories that are only loaded with a save, the dump is
+/// [`DumpStatus::Partial`] without them.
+const DUMP_SAVE_REQUIRED: [&str; 1] = ["Osiris Functions"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DumpStatus {
+    Complete,
+    /// A category of [`DUMP_SAVE_REQUIRED`] wasn't loaded
+    Partial,
+}
+
+/// Waits until the templates and prototypes are loaded, then writes every
+/// category that has items to `dir`.
+///
+/// A category counts as loaded once its source has items and their number
+/// didn't change since the previous poll. If `timeout` runs out, whatever is
+/// loaded by then is written. Fails if a category of [`DUMP_REQUIRED`] is
+/// empty.
+pub(crate) fn dump(
+    dir: &Path,
+    format: ExportFormat,
+    timeout: Duration,
+) -> anyhow::Result<DumpStatus> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    let mut search = Search::default();
+    let query = Query::default();
+    let start = Instant::now();
+    let mut prev_counts = None;
+    loop {
+        let counts = (0..CATEGORIES.len())
+            .map(|i| {
+                search.cur_category = i;
+                choose_category!(search, search(&query))?;
+                Some(choose_category!(search, items.len()))
+            })
+            .collect::<Vec<_>>();
+        let required_loaded = CATEGORIES
+            .iter()
+            .zip(&counts)
+            .all(|(name, x)| !DUMP_REQUIRED.contains(name) || x.is_some_and(|x| x != 0));
+        if required_loaded && prev_counts.as_ref().is_some_and(|x| *x == counts) {
+            break;
+        }
+        if start.elapsed() >= timeout {
+            warn!("timed out waiting for the game data to load");
+            break;
+        }
+        prev_counts = Some(counts);
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let mut missing = Vec::new();
+    let mut status = DumpStatus::Complete;
+    for (i, category) in CATEGORIES.iter().enumerate() {
+        search.cur_category = i;
+        if choose_category!(search, items.is_empty()) {
+            if DUMP_REQUIRED.contains(category) {
+                missing.push(*category);
+            } else if DUMP_SAVE_REQUIRED.contains(category) {
+                warn!("no data loaded for {category}, it needs a save to be loaded");
+                status = DumpStatus::Partial;
+            } else {
+                warn!("no data loaded for {category}, skipping it");
+            }
+            continue;
+        }
+        let path = dir.join(export_file_name(category, format));
+        std::fs::write(
+            &path,
+            choose_category!(search, export(&category.replace(' ', ""), format))?,
+        )?;
+        info!("Dumped {category} to {}", path.display());
+    }
+
+    if !missing.is_empty() {
+        anyhow::bail!("no data loaded for {}", missing.join(", "));
+    }
+    Ok(status)
+}
+
+/// Names of the categories [`search_objects`] and [`find_object`] accept.
+pub(crate) fn categories() -> &'static [&'static str] {
+    &CATEGORIES
+}
+
+/// Runs the query `text` on `category`, returning every field of the first
+/// `limit` matches.
+pub(crate) fn search_objects(
+    category: &str,
+    text: &str,
+    limit: usize,
+) -> anyhow::Result<Vec<ExportValue>> {
+    let mut search = Search::with_category(category)?;
+    let query = Query::parse(text, &search.options, choose_category!(search, columns.as_ref()))?;
+    choose_category!(search, search(&query))
+        .ok_or_else(|| anyhow!("no data loaded for {category}"))?;
+    Ok(choose_category!(
+        search,
+        items.iter().take(limit).map(|x| export_item(x, &mut ExportCtx::default())).collect()
+    ))
+}
+
+/// Every field of the object of `category` identified by `id`, see
+/// [`ObjectTable::find`].
+pub(crate) fn find_object(category: &str, id: &str) -> anyhow::Result<Option<ExportValue>> {
+    let mut search = Search::with_category(category)?;
+    choose_category!(search, search(&Query::default()))
+        .ok_or_else(|| anyhow!("no data loaded for {category}"))?;
+    Ok(choose_category!(search, find(id).map(|x| export_item(x, &mut ExportCtx::default()))))
+}
+
+pub(crate) fn templates() -> Option<impl Iterator<Item = gd::Template<'static>>> {
+    let template_manager = *Globals::static_symbols().ls__GlobalTemplateManager?;
     let template_bank = template_manager.global_template_bank();
 
-    template_bank.templates.iter().map(|x| x.value.as_ref().into())
+    Some(template_bank.templates.iter().map(|x| x.value.as_ref().into()))
 }
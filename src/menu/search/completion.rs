@@ -0,0 +1,376 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use super::{
+    functions::{Function, FunctionCategory},
+    passives::PassiveCategory,
+    spells::SpellCategory,
+    statuses::StatusCategory,
+    table::TableItemCategory,
+    templates::ItemCategory,
+};
+use crate::{game_definitions::ValueType, wrappers::script::ScriptEnv};
+
+/// The game data is reloaded when a new save is loaded, so are the
+/// candidates.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+/// Matches listed in the console output when Tab is ambiguous
+const MAX_LISTED: usize = 40;
+
+/// Tab completion candidates for the console, read from the same sources as
+/// the Game Data Explorer tables.
+#[derive(Debug)]
+pub(crate) struct Completions {
+    /// Overloads by lowercase name
+    functions: BTreeMap<String, Vec<Function>>,
+    statuses: Vec<String>,
+    spells: Vec<String>,
+    passives: Vec<String>,
+    /// Item templates as `Name_GUID`, which is how Osiris refers to them
+    templates: Vec<String>,
+    loaded: Instant,
+}
+
+/// What Tab does to the word before the cursor.
+#[derive(Debug)]
+pub(crate) struct Completion {
+    /// Byte offset of the replaced word
+    pub start: usize,
+    pub text: String,
+    /// The matches, listed in the console when there is more than one
+    pub listed: Vec<String>,
+}
+
+/// Where the cursor is, from the text before it.
+#[derive(Debug)]
+struct Context<'a> {
+    /// The word being completed
+    word: &'a str,
+    word_start: usize,
+    /// Innermost unclosed call and the index of the argument being typed,
+    /// `None` in a list or outside of any call.
+    call: Option<(&'a str, usize)>,
+    in_parens: bool,
+}
+
+impl Completions {
+    pub fn load() -> Self {
+        let mut functions = BTreeMap::<_, Vec<_>>::new();
+        for f in FunctionCategory::source().into_iter().flatten() {
+            functions.entry(f.name().to_lowercase()).or_default().push(f);
+        }
+        let names = |x: Option<Vec<String>>| {
+            let mut x = x.unwrap_or_default();
+            x.sort_unstable();
+            x.dedup();
+            x
+        };
+
+        Self {
+            functions,
+            statuses: names(StatusCategory::source().map(|x| x.filter_map(|x| x.name).collect())),
+            spells: names(SpellCategory::source().map(|x| x.filter_map(|x| x.name).collect())),
+            passives: names(PassiveCategory::source().map(|x| x.filter_map(|x| x.name).collect())),
+            templates: names(
+                ItemCategory::source()
+                    .map(|x| x.filter_map(|x| Some(format!("{}_{}", x.name, x.id?))).collect()),
+            ),
+            loaded: Instant::now(),
+        }
+    }
+
+    /// Nothing is loaded until a save is, so empty candidates are retried
+    /// sooner.
+    pub fn is_stale(&self) -> bool {
+        let interval = if self.functions.is_empty() || self.statuses.is_empty() {
+            RELOAD_INTERVAL / 10
+        } else {
+            RELOAD_INTERVAL
+        };
+        self.loaded.elapsed() > interval
+    }
+
+    /// Completes the word ending at `text`'s end, which is the text before
+    /// the cursor.
+    pub fn complete(&self, text: &str, env: &ScriptEnv) -> Option<Completion> {
+        let cx = Context::new(text);
+        let word = cx.word.to_lowercase();
+
+        let mut candidates = Vec::new();
+        let mut is_function = false;
+        if let Some(var) = word.strip_prefix('$') {
+            let names = env.variables.keys().map(String::as_str).chain(["host", "last"]);
+            candidates.extend(names.filter(|x| x.starts_with(var)).map(|x| format!("${x}")));
+            candidates.sort_unstable();
+            candidates.dedup();
+        } else if cx.call.is_none() && !cx.in_parens {
+            is_function = true;
+            let macros = env.macros().map(|(name, _)| name).collect::<Vec<_>>();
+            let functions = self.functions.values().map(|x| x[0].name());
+            candidates = matching(functions.chain(macros), &word);
+        } else {
+            let values = self.values(cx.call);
+            candidates = matching(values.iter().flat_map(|x| x.iter().map(String::as_str)), &word);
+        }
+
+        let listed = if candidates.len() > 1 {
+            candidates
+                .iter()
+                .take(MAX_LISTED)
+                .map(|x| match self.functions.get(&x.to_lowercase()) {
+                    Some(overloads) if is_function => {
+                        overloads.iter().map(|x| x.signature()).collect::<Vec<_>>().join(" | ")
+                    }
+                    _ => x.clone(),
+                })
+                .chain(
+                    (candidates.len() > MAX_LISTED)
+                        .then(|| format!("... {} more", candidates.len() - MAX_LISTED)),
+                )
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let text = match candidates.as_slice() {
+            [] => return None,
+            [x] if is_function => format!("{x}("),
+            [x] => x.clone(),
+            [first, rest @ ..] => {
+                let len = rest.iter().fold(first.len(), |len, x| {
+                    len.min(
+                        first
+                            .chars()
+                            .zip(x.chars())
+                            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                            .map(|(a, _)| a.len_utf8())
+                            .sum(),
+                    )
+                });
+                // Substring matches may not start with the word
+                if len > cx.word.len()
+                    && first.get(..cx.word.len()).is_some_and(|x| x.eq_ignore_ascii_case(cx.word))
+                {
+                    first[..len].to_string()
+                } else {
+                    cx.word.to_string()
+                }
+            }
+        };
+        Some(Completion { start: cx.word_start, text, listed })
+    }
+
+    /// Signatures of the function being called, or of the one whose name is
+    /// being typed.
+    pub fn hint(&self, text: &str, env: &ScriptEnv) -> Option<String> {
+        let cx = Context::new(text);
+        let name = cx.call.map(|(name, _)| name).unwrap_or(cx.word);
+        if name.is_empty() {
+            return None;
+        }
+        if let Some(overloads) = self.functions.get(&name.to_lowercase()) {
+            let signatures = overloads.iter().map(Function::signature).collect::<Vec<_>>();
+            return Some(signatures.join(" | "));
+        }
+        env.macros().find(|(x, _)| x.eq_ignore_ascii_case(name)).map(|(_, x)| format!("macro {x}"))
+    }
+
+    /// Names that fit the argument being typed, all of them if its type is
+    /// unknown.
+    fn values(&self, call: Option<(&str, usize)>) -> Vec<&[String]> {
+        let all = vec![&self.statuses[..], &self.spells, &self.passives, &self.templates];
+        let Some((name, i)) = call else {
+            return all;
+        };
+        let Some(r#type) = self
+            .functions
+            .get(&name.to_lowercase())
+            .and_then(|x| x.iter().find_map(|x| x.params().get(i)))
+        else {
+            return all;
+        };
+
+        match r#type {
+            ValueType::String => {
+                let name = name.to_lowercase();
+                if name.contains("status") {
+                    vec![&self.statuses[..]]
+                } else if name.contains("spell") {
+                    vec![&self.spells[..]]
+                } else if name.contains("passive") {
+                    vec![&self.passives[..]]
+                } else {
+                    vec![&self.statuses[..], &self.spells, &self.passives]
+                }
+            }
+            ValueType::GuidString | ValueType::Root | ValueType::ItemRoot => {
+                vec![&self.templates[..]]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<'a> Context<'a> {
+    fn new(text: &'a str) -> Self {
+        let is_word = |x: char| x.is_ascii_alphanumeric() || matches!(x, '_' | '-' | '.' | '$');
+
+        // (function name, argument index), the name is empty for lists
+        let mut stack = Vec::<(&str, usize)>::new();
+        let mut in_string = false;
+        let mut word_start = 0;
+        for (i, char) in text.char_indices() {
+            match char {
+                '"' => in_string = !in_string,
+                _ if in_string => (),
+                '(' | '[' => {
+                    let before = text[..i].trim_end();
+                    let name = if char == '(' {
+                        let start = before.rfind(|x| !is_word(x)).map_or(0, |x| x + 1);
+                        &before[start..]
+                    } else {
+                        ""
+                    };
+                    stack.push((name, 0));
+                }
+                ')' | ']' => {
+                    stack.pop();
+                }
+                ',' => {
+                    if let Some((_, arg)) = stack.last_mut() {
+                        *arg += 1;
+                    }
+                }
+                _ => (),
+            }
+            if !in_string && !is_word(char) || char == '"' {
+                word_start = i + char.len_utf8();
+            }
+        }
+
+        Self {
+            word: &text[word_start..],
+            word_start,
+            call: stack.last().filter(|(name, _)| !name.is_empty()).copied(),
+            in_parens: !stack.is_empty(),
+        }
+    }
+}
+
+/// Candidates starting with `word`, which is lowercase, or containing it if
+/// none do.
+fn matching<'a>(candidates: impl Iterator<Item = &'a str>, word: &str) -> Vec<String> {
+    let (mut starting, containing) = candidates
+        .map(|x| (x, x.to_lowercase()))
+        .filter(|(_, lower)| lower.contains(word))
+        .partition::<Vec<_>, _>(|(_, lower)| lower.starts_with(word));
+    if starting.is_empty() {
+        starting = containing;
+    }
+    starting.sort_unstable_by(|(_, a), (_, b)| a.cmp(b));
+    starting.dedup_by(|(_, a), (_, b)| a == b);
+    starting.into_iter().map(|(x, _)| x.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions() -> Completions {
+        let names = |x: &[&str]| x.iter().map(|x| x.to_string()).collect();
+        Completions {
+            functions: BTreeMap::new(),
+            statuses: names(&["BLESSED", "BURNING", "BURNING_HOLY"]),
+            spells: names(&["Projectile_FireBolt"]),
+            passives: Vec::new(),
+            templates: names(&["LOOT_Gold_A_1c3c9c74-34a1-4685-989e-410dc080be6f"]),
+            loaded: Instant::now(),
+        }
+    }
+
+    fn context(text: &str) -> (&str, usize, Option<(&str, usize)>, bool) {
+        let cx = Context::new(text);
+        (cx.word, cx.word_start, cx.call, cx.in_parens)
+    }
+
+    #[test]
+    fn context_outside_calls() {
+        assert_eq!(context(""), ("", 0, None, false));
+        assert_eq!(context("Apply"), ("Apply", 0, None, false));
+        assert_eq!(context("let $a = 1; Apply"), ("Apply", 12, None, false));
+        assert_eq!(context("Foo(1); Bar"), ("Bar", 8, None, false));
+    }
+
+    #[test]
+    fn context_in_calls() {
+        assert_eq!(context("ApplyStatus("), ("", 12, Some(("ApplyStatus", 0)), true));
+        assert_eq!(context("ApplyStatus($host, BUR"), ("BUR", 19, Some(("ApplyStatus", 1)), true));
+        assert_eq!(context("ApplyStatus ($host"), ("$host", 13, Some(("ApplyStatus", 0)), true));
+        assert_eq!(context("Foo(Bar(1, 2), Baz(x"), ("x", 19, Some(("Baz", 0)), true));
+        assert_eq!(context("Foo(Bar(1, 2), x"), ("x", 15, Some(("Foo", 1)), true));
+    }
+
+    #[test]
+    fn context_in_lists() {
+        assert_eq!(context("for $x in [a, b"), ("b", 14, None, true));
+        assert_eq!(context("Foo([a, b"), ("b", 8, None, true));
+        assert_eq!(context("[Foo(1, "), ("", 8, Some(("Foo", 1)), true));
+        assert_eq!(context("Foo([a, b], c"), ("c", 12, Some(("Foo", 1)), true));
+    }
+
+    #[test]
+    fn context_in_strings() {
+        assert_eq!(context("Foo(\"a(b, c\", d"), ("d", 14, Some(("Foo", 1)), true));
+        assert_eq!(context("Foo(\"Fire B"), ("Fire B", 5, Some(("Foo", 0)), true));
+        assert_eq!(context("Foo(\"a)\", "), ("", 10, Some(("Foo", 1)), true));
+    }
+
+    #[test]
+    fn matching_prefers_prefixes() {
+        let names = ["BURNING", "BLESSED", "Burning_Holy", "WILDBURN"];
+        assert_eq!(matching(names.into_iter(), "bur"), ["BURNING", "Burning_Holy"]);
+        assert_eq!(matching(names.into_iter(), "b"), ["BLESSED", "BURNING", "Burning_Holy"]);
+        assert_eq!(matching(names.into_iter(), "ing"), ["BURNING", "Burning_Holy"]);
+        assert_eq!(matching(names.into_iter(), "").len(), 4);
+        assert!(matching(names.into_iter(), "frost").is_empty());
+        assert_eq!(matching(["Foo", "foo"].into_iter(), "f").len(), 1);
+    }
+
+    #[test]
+    fn complete_extends_common_prefix() {
+        let env = ScriptEnv::default();
+        let x = completions().complete("ApplyStatus($host, bur", &env).unwrap();
+        assert_eq!((x.start, x.text.as_str()), (19, "BURNING"));
+        assert_eq!(x.listed, ["BURNING", "BURNING_HOLY"]);
+
+        let x = completions().complete("ApplyStatus($host, burning_", &env).unwrap();
+        assert_eq!(x.text, "BURNING_HOLY");
+        assert!(x.listed.is_empty());
+
+        // Substring matches don't share a prefix with the word
+        let x = completions().complete("ApplyStatus($host, ing", &env).unwrap();
+        assert_eq!(x.text, "ing");
+        assert_eq!(x.listed.len(), 2);
+
+        let x = completions().complete("Foo(gold", &env).unwrap();
+        assert_eq!(x.text, "LOOT_Gold_A_1c3c9c74-34a1-4685-989e-410dc080be6f");
+        assert!(completions().complete("Foo(frost", &env).is_none());
+    }
+
+    #[test]
+    fn complete_variables_and_macros() {
+        let mut env = ScriptEnv::default();
+        env.run("let $target = 1\nmacro burnall() { }", &mut String::new()).unwrap();
+
+        let x = completions().complete("Foo($t", &env).unwrap();
+        assert_eq!((x.start, x.text.as_str()), (4, "$target"));
+        let x = completions().complete("$", &env).unwrap();
+        assert_eq!(x.text, "$");
+        assert_eq!(x.listed, ["$host", "$last", "$target"]);
+
+        let x = completions().complete("burn", &env).unwrap();
+        assert_eq!(x.text, "burnall(");
+    }
+}
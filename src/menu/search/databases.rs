@@ -0,0 +1,193 @@
+use game_object::GameObject;
+use imgui::{ListClipper, TableColumnSetup, TableFlags, TableSortDirection, Ui};
+
+use super::table::TableItemCategory;
+use crate::{
+    game_definitions::{Database, Function, FunctionType, OsiStr, ValueType},
+    globals::Globals,
+};
+
+#[derive(Clone, GameObject)]
+pub(crate) struct OsirisDatabase {
+    #[skip]
+    pub db: Option<&'static Database>,
+    #[column(name = "Name", visible)]
+    pub name: String,
+    #[column(name = "Arity", visible)]
+    pub arity: usize,
+    #[column(name = "Column Types", visible)]
+    pub column_types: String,
+    #[column(name = "Rows", visible)]
+    pub rows: Option<usize>,
+}
+
+impl OsirisDatabase {
+    fn new(name: &OsiStr, f: &Function) -> Self {
+        let name = name.to_string().rsplit_once('/').unwrap().0.into();
+        let types = f
+            .signature
+            .params
+            .params
+            .iter()
+            .map(|x| format!("{:?}", ValueType::from(x.r#type)))
+            .collect::<Vec<_>>();
+
+        let db = Globals::osiris_globals_opt()
+            .and_then(|x| {
+                x.nodes.as_opt().and_then(|x| x.as_opt()).zip(x.databases.as_opt()?.as_opt())
+            })
+            .and_then(|(nodes, databases)| f.database(nodes, databases))
+            // Guards against reading something else than a database
+            .filter(|x| x.facts.iter().next().map_or(true, |x| x.size as usize == types.len()));
+
+        Self {
+            db,
+            name,
+            arity: types.len(),
+            column_types: types.join(", "),
+            rows: db.map(|x| x.facts.size as _),
+        }
+    }
+}
+
+/// Facts of the selected database, cached so they aren't read every frame.
+struct Facts {
+    db: *const Database,
+    columns: Box<[String]>,
+    rows: Vec<Box<[String]>>,
+    filter: String,
+    filtered: Vec<usize>,
+}
+
+impl Facts {
+    fn new(item: &OsirisDatabase, db: &Database) -> Self {
+        let mut facts = Self {
+            db,
+            columns: if item.arity == 0 {
+                Box::default()
+            } else {
+                item.column_types.split(", ").map(String::from).collect()
+            },
+            rows: Vec::new(),
+            filter: String::new(),
+            filtered: Vec::new(),
+        };
+        facts.reload(db);
+        facts
+    }
+
+    fn reload(&mut self, db: &Database) {
+        self.rows = db
+            .facts
+            .iter()
+            .map(|x| x.as_slice().iter().map(ToString::to_string).collect())
+            .collect();
+        self.apply_filter();
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.filtered = (0..self.rows.len())
+            .filter(|i| self.rows[*i].iter().any(|x| x.to_lowercase().contains(&filter)))
+            .collect();
+    }
+
+    fn sort(&mut self, column: usize, direction: TableSortDirection) {
+        self.rows.sort_by(|a, b| {
+            let (a, b) = (&a[column], &b[column]);
+            let ord = match (a.parse::<f64>(), b.parse::<f64>()) {
+                (Ok(a), Ok(b)) => a.total_cmp(&b),
+                _ => a.cmp(b),
+            };
+            match direction {
+                TableSortDirection::Ascending => ord,
+                TableSortDirection::Descending => ord.reverse(),
+            }
+        });
+        self.apply_filter();
+    }
+
+    fn draw(&mut self, ui: &Ui, db: &Database) {
+        ui.text(format!("{} of {} facts", self.filtered.len(), self.rows.len()));
+        ui.same_line();
+        if ui.button("Refresh") {
+            self.reload(db);
+        }
+        if ui.input_text("Filter##facts-filter", &mut self.filter).build() {
+            self.apply_filter();
+        }
+        if self.columns.is_empty() {
+            return;
+        }
+
+        let Some(_tbl) = ui.begin_table_with_sizing(
+            "facts-tbl",
+            self.columns.len(),
+            TableFlags::SCROLL_Y
+                | TableFlags::RESIZABLE
+                | TableFlags::SORTABLE
+                | TableFlags::ROW_BG,
+            [0.0, -1.0],
+            0.0,
+        ) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for col in self.columns.iter() {
+            ui.table_setup_column_with(TableColumnSetup::new(col.as_str()));
+        }
+        ui.table_headers_row();
+        if let Some(specs) = ui.table_sort_specs_mut() {
+            specs.conditional_sort(|specs| {
+                if let Some(specs) = specs.iter().next()
+                    && let Some(direction) = specs.sort_direction()
+                {
+                    self.sort(specs.column_idx(), direction);
+                }
+            });
+        }
+
+        let mut clipper = ListClipper::new(self.filtered.len() as _).begin(ui);
+        while clipper.step() {
+            for i in clipper.display_start()..clipper.display_end() {
+                ui.table_next_row();
+                for value in self.rows[self.filtered[i as usize]].iter() {
+                    ui.table_next_column();
+                    ui.text(value);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct DatabaseCategory {
+    facts: Option<Facts>,
+}
+
+impl TableItemCategory for DatabaseCategory {
+    type Item = OsirisDatabase;
+
+    fn source() -> Option<impl Iterator<Item = Self::Item>> {
+        let fn_db = Globals::osiris_globals_opt()?.functions.as_opt()?.as_opt()?;
+        (fn_db.num_items != 0).then(|| {
+            fn_db
+                .functions()
+                .filter(|(_, f)| matches!(f.r#type, FunctionType::Database))
+                .map(|(k, v)| OsirisDatabase::new(k, v))
+        })
+    }
+
+    fn draw_actions(&mut self, ui: &Ui, item: &mut Self::Item) {
+        let Some(db) = item.db else {
+            ui.text("Unable to locate the facts of this database");
+            return;
+        };
+        if self.facts.as_ref().map_or(true, |x| x.db != db as *const _) {
+            self.facts = Some(Facts::new(item, db));
+        }
+        if let Some(facts) = &mut self.facts {
+            facts.draw(ui, db);
+        }
+    }
+}
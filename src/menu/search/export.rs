@@ -0,0 +1,259 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::{
+    table::TableItem,
+    table_value::{GameObjectFullVisitor, GameObjectVisitor, TableValue},
+};
+
+/// Limit on followed pointers, in case they keep leading to new objects.
+const MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Json,
+    Csv,
+    Lsx,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 3] = [Self::Json, Self::Csv, Self::Lsx];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Lsx => "LSX",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Lsx => "lsx",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.extension().eq_ignore_ascii_case(extension))
+    }
+
+    /// Exports `items` as a single document, `name` is used as the LSX region.
+    pub fn export<'a, T: TableItem + 'a>(
+        self,
+        name: &str,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> anyhow::Result<String> {
+        self.export_values(name, items.into_iter().map(export_row).collect())
+    }
+
+    /// Exports already converted rows, for data that isn't a [`TableItem`].
+    pub fn export_values(self, name: &str, rows: Vec<ExportValue>) -> anyhow::Result<String> {
+        match self {
+            Self::Json => Ok(serde_json::to_string_pretty(&serde_json::Value::Array(
+                rows.into_iter().map(Into::into).collect(),
+            ))?),
+            Self::Csv => Ok(to_csv(&rows)),
+            Self::Lsx => Ok(to_lsx(name, &rows)?),
+        }
+    }
+}
+
+/// Game data converted to a tree of strings.
+#[derive(Debug, Clone)]
+pub(crate) enum ExportValue {
+    Null,
+    Value(String),
+    List(Vec<ExportValue>),
+    Object(Vec<(String, ExportValue)>),
+}
+
+impl From<ExportValue> for serde_json::Value {
+    fn from(value: ExportValue) -> Self {
+        match value {
+            ExportValue::Null => Self::Null,
+            ExportValue::Value(x) => Self::String(x),
+            ExportValue::List(x) => Self::Array(x.into_iter().map(Into::into).collect()),
+            ExportValue::Object(x) => {
+                Self::Object(x.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+        }
+    }
+}
+
+/// Tracks the pointers being exported to avoid following reference cycles,
+/// e.g. from a spell to its parent and back to its children.
+#[derive(Debug, Default)]
+pub(crate) struct ExportCtx {
+    ancestors: Vec<usize>,
+}
+
+impl ExportCtx {
+    /// Exports the pointee unless it is already being exported further up.
+    pub fn pointer<T: TableValue>(&mut self, ptr: *const T) -> ExportValue {
+        let Some(item) = (unsafe { ptr.as_ref() }) else {
+            return ExportValue::Null;
+        };
+        let addr = ptr as usize;
+        if self.ancestors.contains(&addr) || self.ancestors.len() >= MAX_DEPTH {
+            return ExportValue::Value(format!("{ptr:?}"));
+        }
+        self.ancestors.push(addr);
+        let value = item.export(self);
+        self.ancestors.pop();
+        value
+    }
+}
+
+/// Exports every field of `item` with [`TableItem::visit_all`].
+pub(crate) fn export_item(item: &impl TableItem, ctx: &mut ExportCtx) -> ExportValue {
+    item.visit_all(ExportVisitor { ctx, entries: Vec::new() })
+}
+
+fn export_row(item: &impl TableItem) -> ExportValue {
+    export_item(item, &mut ExportCtx::default())
+}
+
+#[derive(Debug)]
+struct ExportVisitor<'a> {
+    ctx: &'a mut ExportCtx,
+    entries: Vec<(String, ExportValue)>,
+}
+
+impl GameObjectVisitor for ExportVisitor<'_> {
+    type Return = ();
+
+    fn visit(&mut self, name: impl AsRef<str>, item: &impl TableValue) {
+        self.entries.push((name.as_ref().into(), item.export(self.ctx)));
+    }
+}
+
+impl GameObjectFullVisitor for ExportVisitor<'_> {
+    type Finish = ExportValue;
+
+    fn finish(self) -> Self::Finish {
+        ExportValue::Object(self.entries)
+    }
+}
+
+/// One row per item, nested fields are flattened into `Parent.Child` columns.
+fn to_csv(rows: &[ExportValue]) -> String {
+    fn flatten(prefix: String, value: &ExportValue, out: &mut Vec<(String, String)>) {
+        let join =
+            |name: &str| if prefix.is_empty() { name.into() } else { format!("{prefix}.{name}") };
+        match value {
+            ExportValue::Null => out.push((prefix, String::new())),
+            ExportValue::Value(x) => out.push((prefix, x.clone())),
+            ExportValue::List(x) => {
+                for (i, x) in x.iter().enumerate() {
+                    flatten(join(&i.to_string()), x, out);
+                }
+            }
+            ExportValue::Object(x) => {
+                for (name, x) in x {
+                    flatten(join(name), x, out);
+                }
+            }
+        }
+    }
+    let field = |x: &str| {
+        if x.contains([',', '"', '\n']) {
+            format!("\"{}\"", x.replace('"', "\"\""))
+        } else {
+            x.to_owned()
+        }
+    };
+
+    let mut columns = Vec::new();
+    let mut column_indices = HashMap::new();
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let mut fields = Vec::new();
+            flatten(String::new(), row, &mut fields);
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let i = *column_indices.entry(name.clone()).or_insert_with(|| {
+                        columns.push(name);
+                        columns.len() - 1
+                    });
+                    (i, value)
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut csv = columns.iter().map(|x| field(x)).collect::<Vec<_>>().join(",");
+    csv.push('\n');
+    for row in rows {
+        csv.push_str(
+            &(0..columns.len())
+                .map(|i| row.get(&i).map(|x| field(x)).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Larian's LSX layout: a region with a node per item, values are
+/// attributes and nested objects and lists are child nodes.
+fn to_lsx(region: &str, rows: &[ExportValue]) -> Result<String, std::fmt::Error> {
+    fn escape(x: &str) -> String {
+        x.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+    fn node(out: &mut String, indent: usize, id: &str, value: &ExportValue) -> std::fmt::Result {
+        let pad = "    ".repeat(indent);
+        let (attributes, children) = match value {
+            ExportValue::Null => return Ok(()),
+            ExportValue::Value(_) => (vec![("Value", value)], Vec::new()),
+            ExportValue::List(x) => (Vec::new(), x.iter().map(|x| ("Item", x)).collect()),
+            ExportValue::Object(x) => x
+                .iter()
+                .filter(|x| !matches!(x.1, ExportValue::Null))
+                .map(|(k, v)| (k.as_str(), v))
+                .partition(|x| matches!(x.1, ExportValue::Value(_))),
+        };
+
+        writeln!(out, "{pad}<node id=\"{}\">", escape(id))?;
+        for (id, value) in attributes {
+            if let ExportValue::Value(x) = value {
+                writeln!(
+                    out,
+                    "{pad}    <attribute id=\"{}\" type=\"LSString\" value=\"{}\" />",
+                    escape(id),
+                    escape(x)
+                )?;
+            }
+        }
+        if !children.is_empty() {
+            writeln!(out, "{pad}    <children>")?;
+            for (id, value) in children {
+                node(out, indent + 2, id, value)?;
+            }
+            writeln!(out, "{pad}    </children>")?;
+        }
+        writeln!(out, "{pad}</node>")
+    }
+
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(out, "<save>")?;
+    writeln!(out, "    <region id=\"{}\">", escape(region))?;
+    writeln!(out, "        <node id=\"root\">")?;
+    writeln!(out, "            <children>")?;
+    for row in rows {
+        node(&mut out, 4, "Item", row)?;
+    }
+    writeln!(out, "            </children>")?;
+    writeln!(out, "        </node>")?;
+    writeln!(out, "    </region>")?;
+    writeln!(out, "</save>")?;
+    Ok(out)
+}
@@ -1,4 +1,5 @@
 use imgui::Ui;
+use serde::{Deserialize, Serialize};
 
 use super::{
     table::{ColumnsTableItem, TableColumn, TableItem, TableItemCategory},
@@ -15,6 +16,8 @@ pub(crate) struct Function {
     r#type: game_definitions::FunctionType,
     args: Vec<String>,
     ret_type: Option<String>,
+    /// Types of the arguments passed by the caller, without the out params
+    params: Vec<ValueType>,
 }
 
 impl Function {
@@ -22,6 +25,7 @@ impl Function {
         let name = name.to_string().rsplit_once('/').unwrap().0.into();
         let mut args = Vec::with_capacity(f.signature.params.params.size as _);
         let mut ret_type = None;
+        let mut params = Vec::new();
         for (i, arg) in f.signature.params.params.iter().enumerate() {
             if f.signature.out_param_list.is_out_param(i) {
                 if ret_type.is_none() {
@@ -31,10 +35,28 @@ impl Function {
                 }
             } else {
                 args.push(format!("{:?}", ValueType::from(arg.r#type)));
+                params.push(ValueType::from(arg.r#type));
             }
         }
 
-        Self { name, r#type: f.r#type, args, ret_type }
+        Self { name, r#type: f.r#type, args, ret_type, params }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn params(&self) -> &[ValueType] {
+        &self.params
+    }
+
+    /// `Name(Type, ...) -> Ret`, as shown in the table.
+    pub fn signature(&self) -> String {
+        if let Some(ret) = &self.ret_type {
+            format!("{}({}) -> {ret}", self.name, self.args.join(", "))
+        } else {
+            format!("{}({})", self.name, self.args.join(", "))
+        }
     }
 }
 
@@ -62,36 +84,22 @@ impl ColumnsTableItem for Function {
 impl TableItem for Function {
     fn visit<T: GameObjectVisitor>(&self, visitor: &mut T, i: usize) -> T::Return {
         match i {
-            0 => visitor.visit(
-                "Signature",
-                &if let Some(ret) = &self.ret_type {
-                    format!("{}({}) -> {ret}", self.name, self.args.join(", "))
-                } else {
-                    format!("{}({})", self.name, self.args.join(", "))
-                },
-            ),
+            0 => visitor.visit("Signature", &self.signature()),
             1 => visitor.visit("Type", &self.r#type.to_string()),
             _ => unreachable!(),
         }
     }
 
-    fn visit_field<T: GameObjectVisitor>(
-        &self,
-        _visitor: &mut T,
-        _name: &str,
-    ) -> Option<T::Return> {
-        unimplemented!()
+    fn visit_field<T: GameObjectVisitor>(&self, visitor: &mut T, name: &str) -> Option<T::Return> {
+        match name {
+            "Signature" => Some(self.visit(visitor, 0)),
+            "Type" => Some(self.visit(visitor, 1)),
+            _ => None,
+        }
     }
 
     fn visit_all<T: GameObjectFullVisitor>(&self, mut visitor: T) -> T::Finish {
-        visitor.visit(
-            "Signature",
-            &if let Some(ret) = &self.ret_type {
-                format!("{}({}) -> {ret}", self.name, self.args.join(", "))
-            } else {
-                format!("{}({})", self.name, self.args.join(", "))
-            },
-        );
+        visitor.visit("Signature", &self.signature());
         visitor.visit("Type", &self.r#type.to_string());
         for (i, arg) in self.args.iter().enumerate() {
             visitor.visit(format!("Arg {i}"), arg);
@@ -103,7 +111,8 @@ impl TableItem for Function {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub(crate) struct FunctionCategory {
     incl_unknown: bool,
     incl_event: bool,
@@ -136,7 +145,7 @@ impl TableItemCategory for FunctionCategory {
     type Item = Function;
 
     fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        let fn_db = Globals::osiris_globals().functions.as_opt()?.as_opt()?;
+        let fn_db = Globals::osiris_globals_opt()?.functions.as_opt()?.as_opt()?;
         (fn_db.num_items != 0).then(|| fn_db.functions().map(|(k, v)| Function::new(k, v)))
     }
 
@@ -154,6 +163,16 @@ impl TableItemCategory for FunctionCategory {
         }
     }
 
+    fn options(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+
+    fn set_options(&mut self, options: &serde_json::Value) {
+        if let Ok(x) = Self::deserialize(options) {
+            *self = x;
+        }
+    }
+
     fn draw_options(&mut self, ui: &Ui) -> bool {
         let mut changed = false;
         if let Some(node) = ui.tree_node("Function Types") {
@@ -0,0 +1,129 @@
+use game_object::GameObject;
+use imgui::{TreeNodeFlags, Ui};
+use itertools::Itertools;
+
+use super::table::TableItemCategory;
+use crate::{
+    game_definitions::{Goal, GoalDb, GoalRef, GoalState, List},
+    globals::Globals,
+};
+
+/// Sub-goal trees deeper than this are cut off, in case the goals form a cycle.
+const MAX_TREE_DEPTH: usize = 32;
+
+#[derive(Clone, GameObject)]
+pub(crate) struct OsirisGoal {
+    #[skip]
+    pub goal: &'static Goal,
+    #[column(name = "Name", visible)]
+    pub name: String,
+    #[column(name = "State", visible)]
+    pub state: GoalState,
+    #[column(name = "Parents", visible)]
+    pub parents: String,
+    #[column(name = "Sub-goals")]
+    pub sub_goals: String,
+    #[column(name = "Init Actions")]
+    pub init_actions: String,
+    #[column(name = "Exit Actions")]
+    pub exit_actions: String,
+}
+
+impl OsirisGoal {
+    fn new(goal: &'static Goal, goals: &GoalDb) -> Self {
+        let names = |refs: &List<GoalRef>| {
+            refs.iter().filter_map(|x| goals.get(x.id)).map(|x| x.name()).join(", ")
+        };
+
+        Self {
+            goal,
+            name: goal.name().into(),
+            state: goal.state(),
+            parents: names(&goal.parent_goals),
+            sub_goals: names(&goal.sub_goals),
+            init_actions: goal.init_calls().join("\n"),
+            exit_actions: goal.exit_calls().join("\n"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct GoalCategory;
+
+impl GoalCategory {
+    fn draw_tree(ui: &Ui, goals: &GoalDb, goal: &Goal, selected: &Goal, depth: usize) {
+        let label = format!("{} ({:?})##goal-{}", goal.name(), goal.state(), goal.id);
+        let color = ui.push_style_color(imgui::StyleColor::Text, state_color(goal.state()));
+        let mut flags = TreeNodeFlags::SPAN_AVAIL_WIDTH;
+        if goal.sub_goals.size == 0 {
+            flags |= TreeNodeFlags::LEAF;
+        }
+        if goal.id == selected.id {
+            flags |= TreeNodeFlags::SELECTED;
+        }
+        let node = ui.tree_node_config(label).flags(flags).push();
+        color.pop();
+
+        if let Some(_node) = node {
+            if depth >= MAX_TREE_DEPTH {
+                ui.text("...");
+                return;
+            }
+            for sub_goal in goal.sub_goals.iter().filter_map(|x| goals.get(x.id)) {
+                Self::draw_tree(ui, goals, sub_goal, selected, depth + 1);
+            }
+        }
+    }
+}
+
+impl TableItemCategory for GoalCategory {
+    type Item = OsirisGoal;
+
+    fn source() -> Option<impl Iterator<Item = Self::Item>> {
+        let goals = Globals::osiris_globals_opt()?.goals.as_opt()?.as_opt()?;
+        let elements = goals.elements.as_slice();
+        (!elements.is_empty()).then(|| {
+            elements.iter().filter_map(|x| x.as_opt()).map(move |x| OsirisGoal::new(x, goals))
+        })
+    }
+
+    fn draw_actions(&mut self, ui: &Ui, item: &mut Self::Item) {
+        let Some(goals) =
+            Globals::osiris_globals_opt().and_then(|x| x.goals.as_opt()).and_then(|x| x.as_opt())
+        else {
+            return;
+        };
+
+        ui.separator();
+        ui.text("Hierarchy");
+        // Walk up to the topmost parent so the selected goal is shown in context
+        let mut root = item.goal;
+        for _ in 0..MAX_TREE_DEPTH {
+            match root.parent_goals.iter().next().and_then(|x| goals.get(x.id)) {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+        Self::draw_tree(ui, goals, root, item.goal, 0);
+
+        for (title, actions) in
+            [("Init Actions", &item.init_actions), ("Exit Actions", &item.exit_actions)]
+        {
+            ui.separator();
+            ui.text(title);
+            if actions.is_empty() {
+                ui.text_disabled("None");
+            } else {
+                ui.text_wrapped(actions);
+            }
+        }
+    }
+}
+
+fn state_color(state: GoalState) -> [f32; 4] {
+    match state {
+        GoalState::Sleeping => [0.9, 0.9, 0.3, 1.0],
+        GoalState::Active => [0.4, 0.9, 0.4, 1.0],
+        GoalState::Completed => [0.6, 0.6, 0.6, 1.0],
+    }
+}
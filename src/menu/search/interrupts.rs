@@ -0,0 +1,90 @@
+use game_object::GameObject;
+
+use super::{
+    osiris_helpers::{add_interrupt, is_game_state_running, remove_interrupt},
+    table::TableItemCategory,
+};
+use crate::{
+    err,
+    game_definitions::{FixedString, InterruptPrototype},
+    globals::Globals,
+    wrappers::osiris::Value,
+};
+
+#[derive(Clone, GameObject)]
+pub(crate) struct Interrupt {
+    pub interrupt: &'static InterruptPrototype,
+    #[column(name = "Internal Name", visible)]
+    pub name: Option<String>,
+    #[column(name = "Display Name", visible)]
+    pub display_name: Option<String>,
+    #[column(name = "Description")]
+    pub desc: Option<String>,
+}
+
+impl From<(&FixedString, &'static InterruptPrototype)> for Interrupt {
+    fn from(value: (&FixedString, &'static InterruptPrototype)) -> Self {
+        let name = value.0.get().map(|x| x.to_string());
+        let display_name = value.1.description.display_name.try_into().ok();
+        let desc = value.1.description.description.try_into().ok();
+
+        Self { interrupt: value.1, name, display_name, desc }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct InterruptCategory;
+
+impl InterruptCategory {
+    /// Interrupts are granted by the `UnlockInterrupt` boost, there is no
+    /// query telling whether the host has one.
+    fn draw_buttons(&mut self, ui: &imgui::Ui, item: &mut Interrupt) -> anyhow::Result<()> {
+        if let Some(name) = &item.name {
+            if !is_game_state_running().is_ok_and(|x| x) {
+                ui.text("Waiting for game to load...");
+                ui.disabled(true, || {
+                    ui.button("Add");
+                    ui.same_line();
+                    ui.button("Remove");
+                });
+                return Ok(());
+            }
+
+            if ui.button("Add") {
+                add_interrupt(name)?;
+            }
+            ui.same_line();
+            if ui.button("Remove") {
+                remove_interrupt(name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TableItemCategory for InterruptCategory {
+    type Item = Interrupt;
+
+    fn source() -> Option<impl Iterator<Item = Self::Item>> {
+        let interrupt_manager = Globals::static_symbols().eoc__InterruptPrototypeManager?;
+        Some(
+            interrupt_manager
+                .as_opt()?
+                .as_opt()
+                .filter(|x| x.initialized)?
+                .interrupts
+                .iter()
+                .map(Into::into),
+        )
+    }
+
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.name.as_deref().map(Into::into)
+    }
+
+    fn draw_actions(&mut self, ui: &imgui::Ui, item: &mut Self::Item) {
+        if let Err(e) = self.draw_buttons(ui, item) {
+            err!("failed to add interrupt: {e}");
+        }
+    }
+}
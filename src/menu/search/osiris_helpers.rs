@@ -2,11 +2,27 @@ use anyhow::bail;
 
 use crate::{osi_fn, wrappers::osiris};
 
+/// Shapeshift rules resource of the game's shapeshift spells, which keep the
+/// equipment. It's the `ShapeshiftRule` parameter of Osiris' `Transform`, see
+/// its signature in the Osiris Functions category.
+const TRANSFORM_SHAPESHIFT_RULE: &str = "296bcfb3-9dab-4a93-8ab1-f1c53c6674c9";
+
 pub(crate) fn give_item(uuid: &str, amount: i32) -> anyhow::Result<()> {
     osi_fn!(TemplateAddTo, uuid, get_host_character()?, amount, 1)?;
     Ok(())
 }
 
+/// Spawns `template` at the host with the spawn effect. It's tracked
+/// (`_Untracked` is 0), so it's saved with the level like other characters.
+pub(crate) fn spawn_at_host(template: &str) -> anyhow::Result<Option<osiris::Value>> {
+    osi_fn!(CreateAtObject, template, get_host_character()?, 0, 1, "", 1)
+}
+
+pub(crate) fn transform_host(template: &str) -> anyhow::Result<()> {
+    osi_fn!(Transform, get_host_character()?, template, TRANSFORM_SHAPESHIFT_RULE)?;
+    Ok(())
+}
+
 pub(crate) fn add_spell(name: &str) -> anyhow::Result<()> {
     osi_fn!(AddSpell, get_host_character()?, name, 1, 1)?;
     Ok(())
@@ -76,6 +92,23 @@ pub(crate) fn remove_passive(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+pub(crate) fn add_interrupt(name: &str) -> anyhow::Result<()> {
+    osi_fn!(AddBoosts, get_host_character()?, format!("UnlockInterrupt({name})").as_str(), "", "")?;
+    Ok(())
+}
+
+pub(crate) fn remove_interrupt(name: &str) -> anyhow::Result<()> {
+    osi_fn!(
+        RemoveBoosts,
+        get_host_character()?,
+        format!("UnlockInterrupt({name})").as_str(),
+        1,
+        "",
+        ""
+    )?;
+    Ok(())
+}
+
 pub(crate) fn get_host_character() -> anyhow::Result<osiris::Value> {
     Ok(osi_fn!(GetHostCharacter)?.unwrap())
 }
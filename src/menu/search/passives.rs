@@ -8,6 +8,7 @@ use crate::{
     err,
     game_definitions::{FixedString, PassivePrototype},
     globals::Globals,
+    wrappers::osiris::Value,
 };
 
 #[derive(Clone, GameObject)]
@@ -70,7 +71,7 @@ impl TableItemCategory for PassiveCategory {
     type Item = Passive;
 
     fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        let passive_manager = Globals::static_symbols().eoc__PassivePrototypeManager.unwrap();
+        let passive_manager = Globals::static_symbols().eoc__PassivePrototypeManager?;
         Some(
             passive_manager
                 .as_opt()?
@@ -85,6 +86,10 @@ impl TableItemCategory for PassiveCategory {
         )
     }
 
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.name.as_deref().map(Into::into)
+    }
+
     fn draw_actions(&mut self, ui: &imgui::Ui, item: &mut Self::Item) {
         if let Err(e) = self.draw_buttons(ui, item) {
             err!("failed to add passive: {e}");
@@ -0,0 +1,589 @@
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use regex::{Regex, RegexBuilder};
+
+use super::{
+    table::{SearchVisitor, TableColumn, TableItem},
+    table_value::{GameObjectVisitor, TableValue},
+    Options,
+};
+
+/// Search query, e.g. `Spell.Level>=3 AND Spell.DamageType:Fire`.
+///
+/// A query is made of terms combined with `AND` (or juxtaposition), `OR`
+/// and `NOT` (or a leading `-`), grouped with parentheses. A term is either
+/// a bare value, matched against all the columns included in search, or a
+/// `field<op>value` pair, where the field is a column name, optionally
+/// followed by `.`-separated names of nested fields. Names are matched
+/// ignoring case, spaces and underscores. Values can be quoted with `"` or
+/// given as a regex with `/`.
+///
+/// Operators:
+/// - `:` contains the value (or matches the regex)
+/// - `=`, `!=` equals the value
+/// - `<`, `<=`, `>`, `>=` numeric comparisons
+#[derive(Debug, Default)]
+pub(crate) struct Query {
+    expr: Option<Expr>,
+}
+
+impl Query {
+    pub fn parse(text: &str, opts: &Options, columns: &[TableColumn]) -> Result<Self, QueryError> {
+        let tokens = Lexer::new(text, opts).collect::<Result<Vec<_>, _>>()?;
+        let mut parser = Parser { tokens: tokens.into_iter().peekable(), columns, end: text.len() };
+        if parser.tokens.peek().is_none() {
+            return Ok(Self::default());
+        }
+
+        let expr = parser.or()?;
+        if let Some((pos, token)) = parser.tokens.next() {
+            return Err(QueryError::new(pos, format!("unexpected {token}")));
+        }
+        Ok(Self { expr: Some(expr) })
+    }
+
+    pub fn matches(&self, item: &impl TableItem, columns: &[TableColumn]) -> bool {
+        self.expr.as_ref().is_none_or(|x| x.matches(item, columns))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct QueryError {
+    pos: usize,
+    msg: String,
+}
+
+impl QueryError {
+    fn new(pos: usize, msg: impl Into<String>) -> Self {
+        Self { pos, msg: msg.into() }
+    }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: {}", self.pos + 1, self.msg)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Compares field names ignoring case, spaces and underscores.
+pub(crate) fn field_name_eq(a: &str, b: &str) -> bool {
+    let normalize = |x: &str| {
+        x.chars()
+            .filter(|x| !matches!(x, ' ' | '_'))
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Search string of the field at `path` relative to `item`.
+pub(crate) fn field_str(item: &impl TableValue, path: &[String]) -> Option<String> {
+    if path.is_empty() {
+        Some(SearchVisitor.visit("", item))
+    } else {
+        item.field_str(path)
+    }
+}
+
+#[derive(Debug)]
+struct FieldVisitor<'a>(&'a [String]);
+
+impl GameObjectVisitor for FieldVisitor<'_> {
+    type Return = Option<String>;
+
+    fn visit(&mut self, _name: impl AsRef<str>, item: &impl TableValue) -> Self::Return {
+        field_str(item, self.0)
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+impl Expr {
+    fn matches(&self, item: &impl TableItem, columns: &[TableColumn]) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(item, columns) && b.matches(item, columns),
+            Expr::Or(a, b) => a.matches(item, columns) || b.matches(item, columns),
+            Expr::Not(x) => !x.matches(item, columns),
+            Expr::Term(x) => x.matches(item, columns),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Term {
+    field: Option<Vec<String>>,
+    op: Op,
+    value: Value,
+}
+
+impl Term {
+    fn matches(&self, item: &impl TableItem, columns: &[TableColumn]) -> bool {
+        if let Some(field) = &self.field {
+            item.visit_field(&mut FieldVisitor(&field[1..]), &field[0])
+                .flatten()
+                .is_some_and(|x| self.matches_str(&x))
+        } else {
+            columns
+                .iter()
+                .enumerate()
+                .filter(|(_, col)| col.included_in_search)
+                .any(|(i, _)| self.matches_str(&item.visit(&mut SearchVisitor, i)))
+        }
+    }
+
+    fn matches_str(&self, text: &str) -> bool {
+        match (&self.value, self.op) {
+            (Value::Regex(re), _) => re.is_match(text),
+            (Value::Text { text: value, .. }, Op::Contains) => self.fold(text).contains(value),
+            (Value::Text { text: value, .. }, Op::Eq) => self.fold(text) == *value,
+            (Value::Text { text: value, .. }, Op::Ne) => self.fold(text) != *value,
+            (Value::Text { num, .. }, op) => {
+                let (Some(value), Ok(text)) = (num, text.trim().parse::<f64>()) else {
+                    return false;
+                };
+                match op {
+                    Op::Lt => text < *value,
+                    Op::Le => text <= *value,
+                    Op::Gt => text > *value,
+                    Op::Ge => text >= *value,
+                    Op::Contains | Op::Eq | Op::Ne => unreachable!(),
+                }
+            }
+        }
+    }
+
+    fn fold(&self, text: &str) -> String {
+        match &self.value {
+            Value::Text { case_sensitive: false, .. } => text.to_lowercase(),
+            _ => text.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Contains,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn is_comparison(self) -> bool {
+        matches!(self, Op::Lt | Op::Le | Op::Gt | Op::Ge)
+    }
+}
+
+#[derive(Debug)]
+enum Value {
+    /// Already lowercased if the search is case insensitive
+    Text {
+        text: String,
+        num: Option<f64>,
+        case_sensitive: bool,
+    },
+    Regex(Regex),
+}
+
+#[derive(Debug)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term { field: Option<String>, op: Op, value: Value },
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::And => f.write_str("'AND'"),
+            Token::Or => f.write_str("'OR'"),
+            Token::Not => f.write_str("'NOT'"),
+            Token::Term { .. } => f.write_str("search term"),
+        }
+    }
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    case_sensitive: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str, opts: &Options) -> Self {
+        Self { text, chars: text.char_indices().peekable(), case_sensitive: opts.case_sensitive }
+    }
+
+    fn token(&mut self, pos: usize, c: char) -> Result<Token, QueryError> {
+        match c {
+            '(' => {
+                self.chars.next();
+                return Ok(Token::LParen);
+            }
+            ')' => {
+                self.chars.next();
+                return Ok(Token::RParen);
+            }
+            '-' | '!' => {
+                self.chars.next();
+                return Ok(Token::Not);
+            }
+            '"' | '/' => {
+                return Ok(Token::Term { field: None, op: Op::Contains, value: self.value(pos)? })
+            }
+            _ => (),
+        }
+
+        let word = self.take_while(|x| !x.is_whitespace() && !"()\":=!<>".contains(x));
+        match word {
+            "AND" => return Ok(Token::And),
+            "OR" => return Ok(Token::Or),
+            "NOT" => return Ok(Token::Not),
+            _ => (),
+        }
+
+        let op_pos = self.chars.peek().map_or(self.text.len(), |x| x.0);
+        let op = match self.chars.peek().map(|x| x.1) {
+            Some(':') => Op::Contains,
+            Some('=') => Op::Eq,
+            Some('!') => Op::Ne,
+            Some('<') => Op::Lt,
+            Some('>') => Op::Gt,
+            _ => {
+                let value = self.text_value(word);
+                return Ok(Token::Term { field: None, op: Op::Contains, value });
+            }
+        };
+        self.chars.next();
+        let op = match (op, self.chars.next_if(|x| x.1 == '=')) {
+            (Op::Lt, Some(_)) => Op::Le,
+            (Op::Gt, Some(_)) => Op::Ge,
+            (Op::Ne, Some(_)) => Op::Ne,
+            (Op::Ne, None) => return Err(QueryError::new(op_pos, "expected '=' after '!'")),
+            (op, Some(_)) if op != Op::Eq => {
+                return Err(QueryError::new(op_pos + 1, "unexpected '='"));
+            }
+            (op, _) => op,
+        };
+        if word.is_empty() {
+            return Err(QueryError::new(op_pos, "expected a field name before the operator"));
+        }
+
+        let value_pos = self.chars.peek().map_or(self.text.len(), |x| x.0);
+        let value = self.value(value_pos)?;
+        match &value {
+            Value::Regex(_) if op != Op::Contains => {
+                return Err(QueryError::new(value_pos, "regex can only be used with ':'"));
+            }
+            Value::Text { num: None, .. } if op.is_comparison() => {
+                return Err(QueryError::new(value_pos, "expected a number"));
+            }
+            _ => (),
+        }
+
+        Ok(Token::Term { field: Some(word.into()), op, value })
+    }
+
+    fn value(&mut self, pos: usize) -> Result<Value, QueryError> {
+        match self.chars.peek().map(|x| x.1) {
+            Some('"') => {
+                self.chars.next();
+                let text = self.take_while(|x| x != '"');
+                if self.chars.next().is_none() {
+                    return Err(QueryError::new(pos, "unterminated quote"));
+                }
+                Ok(self.text_value(text))
+            }
+            Some('/') => {
+                self.chars.next();
+                let start = self.chars.peek().map_or(self.text.len(), |x| x.0);
+                let mut escaped = false;
+                let end = loop {
+                    match self.chars.next() {
+                        Some((i, '/')) if !escaped => break i,
+                        Some((_, c)) => escaped = !escaped && c == '\\',
+                        None => return Err(QueryError::new(pos, "unterminated regex")),
+                    }
+                };
+                RegexBuilder::new(&self.text[start..end].replace("\\/", "/"))
+                    .case_insensitive(!self.case_sensitive)
+                    .build()
+                    .map(Value::Regex)
+                    .map_err(|x| QueryError::new(pos, format!("invalid regex: {x}")))
+            }
+            Some(c) if !c.is_whitespace() && c != ')' => {
+                let text = self.take_while(|x| !x.is_whitespace() && x != ')');
+                Ok(self.text_value(text))
+            }
+            _ => Err(QueryError::new(pos, "expected a value")),
+        }
+    }
+
+    fn text_value(&self, text: &str) -> Value {
+        Value::Text {
+            text: if self.case_sensitive { text.into() } else { text.to_lowercase() },
+            num: text.parse().ok(),
+            case_sensitive: self.case_sensitive,
+        }
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let start = self.chars.peek().map_or(self.text.len(), |x| x.0);
+        while self.chars.next_if(|x| pred(x.1)).is_some() {}
+        let end = self.chars.peek().map_or(self.text.len(), |x| x.0);
+        &self.text[start..end]
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Result<(usize, Token), QueryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.chars.next_if(|x| x.1.is_whitespace()).is_some() {}
+        let (pos, c) = *self.chars.peek()?;
+        Some(self.token(pos, c).map(|x| (pos, x)))
+    }
+}
+
+struct Parser<'a, I: Iterator<Item = (usize, Token)>> {
+    tokens: Peekable<I>,
+    columns: &'a [TableColumn],
+    end: usize,
+}
+
+impl<I: Iterator<Item = (usize, Token)>> Parser<'_, I> {
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.and()?;
+        while self.tokens.next_if(|x| matches!(x.1, Token::Or)).is_some() {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.not()?;
+        loop {
+            match self.tokens.peek() {
+                Some((_, Token::And)) => {
+                    self.tokens.next();
+                }
+                Some((_, Token::LParen | Token::Not | Token::Term { .. })) => (),
+                _ => break,
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if self.tokens.next_if(|x| matches!(x.1, Token::Not)).is_some() {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        match self.tokens.next() {
+            Some((pos, Token::LParen)) => {
+                let expr = self.or()?;
+                match self.tokens.next() {
+                    Some((_, Token::RParen)) => Ok(expr),
+                    _ => Err(QueryError::new(pos, "unclosed '('")),
+                }
+            }
+            Some((pos, Token::Term { field, op, value })) => {
+                let field = field.map(|x| self.field(pos, &x)).transpose()?;
+                Ok(Expr::Term(Term { field, op, value }))
+            }
+            Some((pos, token)) => Err(QueryError::new(pos, format!("unexpected {token}"))),
+            None => Err(QueryError::new(self.end, "unexpected end of query")),
+        }
+    }
+
+    /// Resolves the column name, nested field names are resolved when
+    /// matching.
+    fn field(&self, pos: usize, field: &str) -> Result<Vec<String>, QueryError> {
+        let mut path = field.split('.').map(String::from).collect::<Vec<_>>();
+        if path.iter().any(String::is_empty) {
+            return Err(QueryError::new(pos, format!("invalid field '{field}'")));
+        }
+        let Some(col) = self.columns.iter().find(|x| field_name_eq(&x.name, &path[0])) else {
+            return Err(QueryError::new(pos, format!("unknown field '{}'", path[0])));
+        };
+        path[0].clone_from(&col.name);
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> [TableColumn; 2] {
+        [TableColumn::new("Name", true, true), TableColumn::new("Spell Level", true, false)]
+    }
+
+    fn parse_with(text: &str, opts: &Options) -> Result<Option<Expr>, String> {
+        Query::parse(text, opts, &columns()).map(|x| x.expr).map_err(|x| x.to_string())
+    }
+
+    /// Parsed query in a compact form, e.g. `(NOT fire AND Name:"bolt")`.
+    fn parse(text: &str) -> String {
+        fn show(expr: &Expr) -> String {
+            match expr {
+                Expr::And(a, b) => format!("({} AND {})", show(a), show(b)),
+                Expr::Or(a, b) => format!("({} OR {})", show(a), show(b)),
+                Expr::Not(x) => format!("NOT {}", show(x)),
+                Expr::Term(Term { field, op, value }) => {
+                    let value = match value {
+                        Value::Text { text, .. } => format!("{text:?}"),
+                        Value::Regex(x) => format!("/{x}/"),
+                    };
+                    let Some(field) = field else {
+                        return value;
+                    };
+                    let op = match op {
+                        Op::Contains => ":",
+                        Op::Eq => "=",
+                        Op::Ne => "!=",
+                        Op::Lt => "<",
+                        Op::Le => "<=",
+                        Op::Gt => ">",
+                        Op::Ge => ">=",
+                    };
+                    format!("{}{op}{value}", field.join("."))
+                }
+            }
+        }
+        parse_with(text, &Options::default()).unwrap().map_or(String::new(), |x| show(&x))
+    }
+
+    fn term(text: &str, opts: &Options) -> Term {
+        match parse_with(text, opts).unwrap() {
+            Some(Expr::Term(x)) => x,
+            x => panic!("expected a single term, got {x:?}"),
+        }
+    }
+
+    #[test]
+    fn terms() {
+        assert_eq!(parse(""), "");
+        assert_eq!(parse("   "), "");
+        assert_eq!(parse("fire"), "\"fire\"");
+        assert_eq!(parse("fire-bolt"), "\"fire-bolt\"");
+        assert_eq!(parse(" fire\tbolt "), "(\"fire\" AND \"bolt\")");
+        assert_eq!(parse("a AND b OR c"), "((\"a\" AND \"b\") OR \"c\")");
+        assert_eq!(parse("a OR b c"), "(\"a\" OR (\"b\" AND \"c\"))");
+        assert_eq!(parse("(a OR b) c"), "((\"a\" OR \"b\") AND \"c\")");
+        assert_eq!(parse("a(b)"), "(\"a\" AND \"b\")");
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(parse("-fire"), "NOT \"fire\"");
+        assert_eq!(parse("!fire"), "NOT \"fire\"");
+        assert_eq!(parse("NOT fire bolt"), "(NOT \"fire\" AND \"bolt\")");
+        assert_eq!(parse("NOT -fire"), "NOT NOT \"fire\"");
+        assert_eq!(parse("-(a OR b)"), "NOT (\"a\" OR \"b\")");
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(parse("\"Fire Bolt\""), "\"fire bolt\"");
+        assert_eq!(parse("\"a OR (b)\""), "\"a or (b)\"");
+        assert_eq!(parse("\"\""), "\"\"");
+        assert_eq!(parse("name:\"Fire Bolt\" x"), "(Name:\"fire bolt\" AND \"x\")");
+
+        let opts = Options { case_sensitive: true };
+        let Value::Text { text, .. } = term("\"Fire Bolt\"", &opts).value else {
+            panic!("expected text");
+        };
+        assert_eq!(text, "Fire Bolt");
+    }
+
+    #[test]
+    fn field_filters() {
+        assert_eq!(parse("name:fire"), "Name:\"fire\"");
+        assert_eq!(parse("NAME=fire"), "Name=\"fire\"");
+        assert_eq!(parse("name!=fire"), "Name!=\"fire\"");
+        assert_eq!(parse("spell_level<3"), "Spell Level<\"3\"");
+        assert_eq!(parse("SpellLevel<=3"), "Spell Level<=\"3\"");
+        assert_eq!(parse("spelllevel>=-1"), "Spell Level>=\"-1\"");
+        assert_eq!(parse("name.Nested.deep:x"), "Name.Nested.deep:\"x\"");
+        assert_eq!(parse("name:/^fi\\/re$/"), "Name:/^fi/re$/");
+        assert_eq!(parse("(name:a)"), "Name:\"a\"");
+    }
+
+    #[test]
+    fn matching() {
+        let opts = Options::default();
+        let fire = term("Fire", &opts);
+        assert!(fire.matches_str("Fire Bolt"));
+        assert!(fire.matches_str("WILDFIRE"));
+        assert!(!fire.matches_str("Ice"));
+
+        let eq = term("name=Fire", &opts);
+        assert!(eq.matches_str("FIRE"));
+        assert!(!eq.matches_str("Fire Bolt"));
+        assert!(!term("name!=fire", &opts).matches_str("Fire"));
+
+        let level = term("spelllevel>=3", &opts);
+        assert!(level.matches_str("3"));
+        assert!(level.matches_str(" 4.5 "));
+        assert!(!level.matches_str("2"));
+        assert!(!level.matches_str("high"));
+
+        let re = term("/^fi.e$/", &opts);
+        assert!(re.matches_str("FIRE"));
+        assert!(!re.matches_str("fire bolt"));
+
+        let opts = Options { case_sensitive: true };
+        assert!(term("Fire", &opts).matches_str("Fire Bolt"));
+        assert!(!term("Fire", &opts).matches_str("fire bolt"));
+        assert!(!term("/fire/", &opts).matches_str("FIRE"));
+    }
+
+    #[test]
+    fn malformed() {
+        for (text, err) in [
+            ("(a", "column 1: unclosed '('"),
+            ("a)", "column 2: unexpected ')'"),
+            ("a AND", "column 6: unexpected end of query"),
+            ("-", "column 2: unexpected end of query"),
+            ("OR a", "column 1: unexpected 'OR'"),
+            ("\"abc", "column 1: unterminated quote"),
+            ("/abc", "column 1: unterminated regex"),
+            ("name:\"abc", "column 6: unterminated quote"),
+            ("spelllevel>high", "column 12: expected a number"),
+            ("name=/a/", "column 6: regex can only be used with ':'"),
+            ("name!x", "column 5: expected '=' after '!'"),
+            ("name:=x", "column 6: unexpected '='"),
+            (":x", "column 1: expected a field name before the operator"),
+            ("name:", "column 6: expected a value"),
+            ("name: x", "column 6: expected a value"),
+            ("foo:x", "column 1: unknown field 'foo'"),
+            ("name..x:y", "column 1: invalid field 'name..x'"),
+        ] {
+            assert_eq!(parse_with(text, &Options::default()).unwrap_err(), err, "{text}");
+        }
+
+        let err = parse_with("name:/(/", &Options::default()).unwrap_err();
+        assert!(err.starts_with("column 6: invalid regex: "), "{err}");
+    }
+}
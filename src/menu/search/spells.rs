@@ -4,12 +4,14 @@ use super::{
     osiris_helpers::{
         add_spell, add_spell_boost, is_game_state_running, remove_spell, remove_spell_boost,
     },
+    stats::StatsEntry,
     table::TableItemCategory,
 };
 use crate::{
     err,
     game_definitions::{FixedString, SpellPrototype},
     globals::Globals,
+    wrappers::osiris::Value,
 };
 
 #[derive(Clone, GameObject)]
@@ -21,6 +23,9 @@ pub(crate) struct Spell {
     pub display_name: Option<String>,
     #[column(name = "Description")]
     pub desc: Option<String>,
+    /// Entry of `stats_object_index`
+    #[column(name = "Stats")]
+    pub stats: Option<StatsEntry>,
 }
 
 impl From<(&FixedString, &'static SpellPrototype)> for Spell {
@@ -28,8 +33,9 @@ impl From<(&FixedString, &'static SpellPrototype)> for Spell {
         let name = value.0.get().map(|x| x.to_string());
         let display_name = value.1.description.display_name.try_into().ok();
         let desc = value.1.description.description.try_into().ok();
+        let stats = StatsEntry::from_index(value.1.stats_object_index);
 
-        Self { spell: value.1, name, display_name, desc }
+        Self { spell: value.1, name, display_name, desc, stats }
     }
 }
 
@@ -75,7 +81,7 @@ impl TableItemCategory for SpellCategory {
     type Item = Spell;
 
     fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        let spell_manager = Globals::static_symbols().eoc__SpellPrototypeManager.unwrap();
+        let spell_manager = Globals::static_symbols().eoc__SpellPrototypeManager?;
         Some(
             spell_manager
                 .as_opt()?
@@ -87,6 +93,10 @@ impl TableItemCategory for SpellCategory {
         )
     }
 
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.name.as_deref().map(Into::into)
+    }
+
     fn draw_actions(&mut self, ui: &imgui::Ui, item: &mut Self::Item) {
         if let Err(e) = self.draw_buttons(ui, item) {
             err!("failed to add spell: {e}");
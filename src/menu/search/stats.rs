@@ -0,0 +1,222 @@
+use std::{cmp::Ordering, fmt::Debug, ptr};
+
+use game_object::GameObject;
+use imgui::Ui;
+
+use super::{
+    export::{ExportCtx, ExportValue},
+    table::{TableItem, TableItemCategory},
+    table_value::{GameObjectFullVisitor, GameObjectVisitor, TableOrd, TableValue},
+};
+use crate::{
+    game_definitions::{FixedString, ModifierList, RPGEnumeration, RPGStats, StatsObject},
+    globals::Globals,
+    wrappers::osiris::Value,
+};
+
+/// Objects further up the `Using` chain are ignored, in case it loops.
+const MAX_USING_DEPTH: usize = 32;
+
+#[derive(Clone, GameObject)]
+pub(crate) struct StatsEntry {
+    pub object: &'static StatsObject,
+    #[column(name = "Name", visible)]
+    pub name: Option<&'static str>,
+    #[column(name = "Type", visible)]
+    pub kind: Option<&'static str>,
+    #[column(name = "Using", visible)]
+    pub using: Option<&'static str>,
+    #[column(name = "Properties")]
+    pub properties: StatsProperties,
+}
+
+/// Properties of an entry, only resolved when shown, searched or exported,
+/// following `Using` for every entry would slow down loading spells.
+#[derive(Clone, Copy)]
+pub(crate) struct StatsProperties(&'static StatsObject);
+
+#[derive(Clone, GameObject)]
+pub(crate) struct StatsProperty {
+    #[column(name = "Name", visible)]
+    pub name: Option<&'static str>,
+    #[column(name = "Value", visible)]
+    pub value: String,
+    /// The entry's own properties have none
+    #[column(name = "Inherited From", visible)]
+    pub inherited_from: Option<&'static str>,
+}
+
+impl StatsEntry {
+    fn new(stats: &'static RPGStats, object: &'static StatsObject) -> Self {
+        let list = stats.modifier_lists.get(object.modifier_list_index as _);
+        Self {
+            object,
+            name: name(&object.name),
+            kind: list.and_then(|x| name(&x.name)),
+            using: stats.objects.get(object.using).and_then(|x| name(&x.name)),
+            properties: StatsProperties(object),
+        }
+    }
+
+    /// The entry a prototype's `stats_object_index` refers to.
+    pub fn from_index(index: i32) -> Option<Self> {
+        let stats = rpg_stats()?;
+        Some(Self::new(stats, stats.objects.get(index)?))
+    }
+}
+
+impl StatsProperties {
+    fn resolve(self) -> Vec<StatsProperty> {
+        let Some(stats) = rpg_stats() else {
+            return Vec::new();
+        };
+        let list = stats.modifier_lists.get(self.0.modifier_list_index as _);
+        list.map(|x| properties(stats, x, self.0)).unwrap_or_default()
+    }
+}
+
+impl Debug for StatsProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.resolve(), f)
+    }
+}
+
+impl TableValue for StatsProperties {
+    fn type_name() -> String {
+        Vec::<StatsProperty>::type_name()
+    }
+
+    fn export_str(&self) -> String {
+        self.resolve().export_str()
+    }
+
+    fn draw(&self, ui: &Ui) {
+        self.resolve().draw(ui)
+    }
+
+    fn search_str(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.resolve().search_str(f)
+    }
+
+    fn is_defined(&self) -> bool {
+        self.resolve().is_defined()
+    }
+
+    fn is_container(&self) -> bool {
+        true
+    }
+
+    fn field_str(&self, path: &[String]) -> Option<String> {
+        self.resolve().field_str(path)
+    }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        self.resolve().export(ctx)
+    }
+}
+
+impl TableItem for StatsProperties {
+    fn visit<T: GameObjectVisitor>(&self, visitor: &mut T, i: usize) -> T::Return {
+        self.resolve().visit(visitor, i)
+    }
+
+    fn visit_field<T: GameObjectVisitor>(&self, visitor: &mut T, name: &str) -> Option<T::Return> {
+        self.resolve().visit_field(visitor, name)
+    }
+
+    fn visit_all<T: GameObjectFullVisitor>(&self, visitor: T) -> T::Finish {
+        self.resolve().visit_all(visitor)
+    }
+}
+
+impl TableOrd for StatsProperties {
+    fn tbl_cmp(&self, other: &Self) -> Ordering {
+        self.resolve().tbl_cmp(&other.resolve())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct StatsCategory;
+
+impl TableItemCategory for StatsCategory {
+    type Item = StatsEntry;
+
+    fn source() -> Option<impl Iterator<Item = Self::Item>> {
+        let stats = rpg_stats()?;
+        Some(stats.objects.iter().map(move |x| StatsEntry::new(stats, x)))
+    }
+
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.name.map(Into::into)
+    }
+}
+
+fn rpg_stats() -> Option<&'static RPGStats> {
+    Globals::static_symbols().gRPGStats?.as_opt()?.as_opt()
+}
+
+fn name(name: &FixedString) -> Option<&'static str> {
+    name.get().map(|x| x.as_str())
+}
+
+/// Every property of `list` set on `object` or an object it inherits from.
+fn properties(
+    stats: &'static RPGStats,
+    list: &ModifierList,
+    object: &'static StatsObject,
+) -> Vec<StatsProperty> {
+    list.attributes
+        .primitives
+        .iter()
+        .enumerate()
+        .filter_map(|(i, modifier)| {
+            let modifier = modifier.as_opt()?;
+            let enumeration = stats.modifier_value_lists.get(modifier.enumeration_index)?;
+            let (owner, index) = resolve(stats, object, i, enumeration)?;
+            Some(StatsProperty {
+                name: name(&modifier.name),
+                value: value(stats, enumeration, index)?,
+                inherited_from: (!ptr::eq(owner, object)).then(|| name(&owner.name)).flatten(),
+            })
+        })
+        .collect()
+}
+
+/// Object the property at `i` is set on, following `Using` while it's
+/// negative, and its value index. `ConstantInt`s have no unset value.
+fn resolve(
+    stats: &'static RPGStats,
+    mut object: &'static StatsObject,
+    i: usize,
+    enumeration: &RPGEnumeration,
+) -> Option<(&'static StatsObject, i32)> {
+    let is_int = name(&enumeration.name) == Some("ConstantInt");
+    for _ in 0..MAX_USING_DEPTH {
+        let index = *object.indexed_properties.get(i)?;
+        if index >= 0 || is_int {
+            return Some((object, index));
+        }
+        object = stats.objects.get(object.using)?;
+    }
+    None
+}
+
+/// Looks `index` up in the pool of the modifier type, enumerations without a
+/// matching label, e.g. flags, are shown as a number.
+fn value(stats: &RPGStats, enumeration: &RPGEnumeration, index: i32) -> Option<String> {
+    let pool_index = usize::try_from(index).ok();
+    match name(&enumeration.name) {
+        Some("ConstantInt") => Some(index.to_string()),
+        Some("ConstantFloat") => stats.floats.get(pool_index?).map(ToString::to_string),
+        Some("GUID") => stats.guids.get(pool_index?).map(TableValue::export_str),
+        _ if enumeration.values.iter().next().is_some() => Some(
+            enumeration
+                .values
+                .iter()
+                .find(|(_, x)| **x == index)
+                .and_then(|(x, _)| name(x))
+                .map_or_else(|| index.to_string(), Into::into),
+        ),
+        _ => stats.fixed_strings.get(pool_index?).and_then(name).map(Into::into),
+    }
+}
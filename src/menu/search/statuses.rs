@@ -8,6 +8,7 @@ use crate::{
     err,
     game_definitions::{FixedString, StatusPrototype},
     globals::Globals,
+    wrappers::osiris::Value,
 };
 
 #[derive(Clone, GameObject)]
@@ -85,7 +86,7 @@ impl TableItemCategory for StatusCategory {
     type Item = Status;
 
     fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        let status_manager = Globals::static_symbols().eoc__StatusPrototypeManager.unwrap();
+        let status_manager = Globals::static_symbols().eoc__StatusPrototypeManager?;
         Some(
             status_manager
                 .as_opt()?
@@ -97,6 +98,10 @@ impl TableItemCategory for StatusCategory {
         )
     }
 
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.name.as_deref().map(Into::into)
+    }
+
     fn draw_actions(&mut self, ui: &imgui::Ui, item: &mut Self::Item) {
         if let Err(e) = self.draw_buttons(ui, item) {
             err!("failed to add status: {e}");
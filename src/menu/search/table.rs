@@ -6,10 +6,15 @@ use imgui::{
 use itertools::Itertools;
 
 use super::{
+    export::ExportFormat,
+    query::Query,
     table_value::{
         GameObjectFullVisitor, GameObjectParallelVisitor, GameObjectVisitor, TableValue,
     },
-    Options,
+};
+use crate::{
+    menu::settings::{ColumnSettings, TableSettings},
+    wrappers::osiris::Value,
 };
 
 pub(crate) struct ObjectTable<T: TableItemCategory> {
@@ -35,27 +40,17 @@ impl<T: TableItemCategory> Default for ObjectTable<T> {
 }
 
 impl<T: TableItemCategory> ObjectTable<T> {
-    pub fn search(&mut self, string: &str, opts: &Options) -> Option<()> {
+    pub fn search(&mut self, query: &Query) -> Option<()> {
         self.selected.take();
         self.items.clear();
-        let mut search = |string: &str, pred: fn(&str, &str) -> bool| -> Option<()> {
-            self.items.extend(T::source()?.filter(|x| {
-                self.columns.iter().enumerate().filter(|(_, col)| col.included_in_search).any(
-                    |(i, _)| {
-                        pred(x.visit(&mut SearchVisitor, i).as_ref(), string)
-                            && self.category.filter(x)
-                    },
-                )
-            }));
-            Some(())
-        };
-
-        if opts.case_sensitive {
-            search(string, |text, string| text.contains(string))
-        } else {
-            let string = string.to_lowercase();
-            search(&string, |text, string| text.to_lowercase().contains(string))
-        }
+        self.items.extend(
+            T::source()?.filter(|x| self.category.filter(x) && query.matches(x, &self.columns)),
+        );
+        Some(())
+    }
+
+    pub fn export(&self, name: &str, format: ExportFormat) -> anyhow::Result<String> {
+        format.export(name, &self.items)
     }
 
     pub fn draw_table(&mut self, ui: &Ui) {
@@ -182,6 +177,52 @@ impl<T: TableItemCategory> ObjectTable<T> {
         changed
     }
 
+    pub fn settings(&self) -> TableSettings {
+        TableSettings {
+            items_per_page: self.items_per_page,
+            columns: self
+                .columns
+                .iter()
+                .map(|x| {
+                    (x.name.clone(), ColumnSettings {
+                        visible: x.visible,
+                        included_in_search: x.included_in_search,
+                    })
+                })
+                .collect(),
+            options: self.category.options(),
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &TableSettings) {
+        self.items_per_page = settings.items_per_page.max(1);
+        for col in self.columns.iter_mut() {
+            if let Some(x) = settings.columns.get(&col.name) {
+                col.visible = x.visible;
+                col.included_in_search = x.included_in_search;
+            }
+        }
+        if let Some(options) = &settings.options {
+            self.category.set_options(options);
+        }
+    }
+
+    /// Value of the selected item for the console's `$selected` variable.
+    pub fn selected_value(&self) -> Option<Value> {
+        self.category.console_value(&self.items[self.selected?])
+    }
+
+    /// The item whose console value, or any column, is `id`, ignoring case.
+    pub fn find(&self, id: &str) -> Option<&T::Item> {
+        self.items.iter().find(|item| {
+            self.category
+                .console_value(item)
+                .is_some_and(|x| x.to_string().eq_ignore_ascii_case(id))
+                || (0..self.columns.len())
+                    .any(|i| item.visit(&mut SearchVisitor, i).eq_ignore_ascii_case(id))
+        })
+    }
+
     pub fn draw_details(&mut self, ui: &Ui) {
         if let Some(selected) = self.selected {
             let item = &mut self.items[selected];
@@ -360,13 +401,22 @@ pub(crate) trait TableItemCategory: Default {
         false
     }
     fn draw_actions(&mut self, _ui: &Ui, _item: &mut Self::Item) {}
+    /// Osiris argument identifying the item, e.g. a template GUID.
+    fn console_value(&self, _item: &Self::Item) -> Option<Value> {
+        None
+    }
+    /// Category specific options to be persisted in the settings file.
+    fn options(&self) -> Option<serde_json::Value> {
+        None
+    }
+    fn set_options(&mut self, _options: &serde_json::Value) {}
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct TableColumn {
-    name: String,
-    visible: bool,
-    included_in_search: bool,
+    pub name: String,
+    pub visible: bool,
+    pub included_in_search: bool,
 }
 
 impl TableColumn {
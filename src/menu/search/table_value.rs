@@ -3,7 +3,10 @@ use std::{cmp::Ordering, fmt::Debug, ops::Deref};
 use imgui::Ui;
 use itertools::Itertools;
 
-use super::table::{details_view, TableItem};
+use super::{
+    export::{ExportCtx, ExportValue},
+    table::{details_view, TableItem},
+};
 use crate::game_definitions::{
     Array, CompactSet, FixedString, GameHash, GamePtr, Guid, LSStringView, MultiHashMap,
     MultiHashSet, OverrideableProperty, STDString, Set, TranslatedString,
@@ -145,6 +148,14 @@ macro_rules! tbl_ptr {
             fn is_container(&self) -> bool {
                 true
             }
+
+            fn field_str(&self, path: &[String]) -> Option<String> {
+                unsafe { self.as_ref()?.field_str(path) }
+            }
+
+            fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+                ctx.pointer(*self as *const T)
+            }
         }
 
         impl<T> TableOrd for *$mut T {
@@ -162,6 +173,10 @@ impl TableValue for () {
     fn type_name() -> String {
         "void".into()
     }
+
+    fn export(&self, _ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::Null
+    }
 }
 
 impl TableOrd for () {
@@ -218,6 +233,14 @@ impl<T: TableValue> TableValue for Option<T> {
     fn is_defined(&self) -> bool {
         self.is_some()
     }
+
+    fn field_str(&self, path: &[String]) -> Option<String> {
+        self.as_ref()?.field_str(path)
+    }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        self.as_ref().map_or(ExportValue::Null, |x| x.export(ctx))
+    }
 }
 
 impl<T> TableOrd for Option<T>
@@ -288,6 +311,10 @@ impl<T: TableValue, const N: usize> TableValue for [T; N] {
     fn is_container(&self) -> bool {
         true
     }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::List(self.iter().map(|x| x.export(ctx)).collect())
+    }
 }
 
 impl<T: TableValue, const N: usize> TableOrd for [T; N] {
@@ -336,6 +363,10 @@ impl<T: TableValue> TableValue for &[T] {
     fn is_container(&self) -> bool {
         true
     }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::List(self.iter().map(|x| x.export(ctx)).collect())
+    }
 }
 
 impl<T: TableValue> TableOrd for &[T] {
@@ -381,6 +412,10 @@ impl<T: TableValue + Eq + GameHash> TableValue for MultiHashSet<T> {
     fn is_container(&self) -> bool {
         true
     }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::List(self.iter().map(|x| x.export(ctx)).collect())
+    }
 }
 
 impl<T: TableValue + Eq + GameHash> TableOrd for MultiHashSet<T> {
@@ -419,6 +454,10 @@ impl<K: TableValue + Eq + GameHash, V: TableValue> TableValue for MultiHashMap<K
     fn is_container(&self) -> bool {
         true
     }
+
+    fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::Object(self.entries().map(|(k, v)| (k.export_str(), v.export(ctx))).collect())
+    }
 }
 
 impl<K: TableValue + Eq + GameHash, V: TableValue> TableOrd for MultiHashMap<K, V> {
@@ -448,6 +487,14 @@ macro_rules! tbl_value_delegate {
             fn is_container(&self) -> bool {
                 self.$($delegate)*.is_container()
             }
+
+            fn field_str(&self, path: &[String]) -> Option<String> {
+                self.$($delegate)*.field_str(path)
+            }
+
+            fn export(&self, ctx: &mut ExportCtx) -> ExportValue {
+                self.$($delegate)*.export(ctx)
+            }
     };
 }
 
@@ -491,6 +538,22 @@ impl<T: TableValue> TableOrd for Array<T> {
     tbl_ord_delegate!(deref());
 }
 
+impl<T: TableValue> TableValue for Vec<T> {
+    tbl_value_delegate!(as_slice());
+
+    fn type_name() -> String {
+        format!("Vec<{}>", T::type_name())
+    }
+}
+
+impl<V: TableValue> TableItem for Vec<V> {
+    tbl_item_delegate!(as_slice());
+}
+
+impl<T: TableValue> TableOrd for Vec<T> {
+    tbl_ord_delegate!(as_slice());
+}
+
 impl<T: TableValue> TableValue for Set<T> {
     tbl_value_delegate!(deref());
 
@@ -626,6 +689,13 @@ pub(crate) trait TableValue: TableOrd + Debug {
     fn is_container(&self) -> bool {
         false
     }
+    /// Search string of a nested field, used by field queries.
+    fn field_str(&self, _path: &[String]) -> Option<String> {
+        None
+    }
+    fn export(&self, _ctx: &mut ExportCtx) -> ExportValue {
+        ExportValue::Value(self.export_str())
+    }
 }
 
 pub(crate) trait TableOrd {
@@ -2,39 +2,50 @@ use game_object::GameObject;
 use imgui::Ui;
 
 use super::{
-    osiris_helpers::{give_item, is_game_state_running},
+    osiris_helpers::{give_item, is_game_state_running, spawn_at_host, transform_host},
     table::TableItemCategory,
     templates,
 };
 use crate::{
     err,
-    game_definitions::{self as gd, GameObjectTemplate, ItemTemplate, SceneryTemplate},
+    game_definitions::{
+        self as gd, CharacterTemplate, GameObjectTemplate, ItemTemplate, LevelTemplate,
+        LightTemplate, PrefabTemplate, ProjectileTemplate, SceneryTemplate, SurfaceTemplate,
+        TriggerTemplate,
+    },
+    info,
+    wrappers::osiris::Value,
 };
 
-#[derive(Default)]
-pub(crate) struct GameObjectTemplateCategory;
-impl TableItemCategory for GameObjectTemplateCategory {
-    type Item = &'static GameObjectTemplate;
+/// Categories listing the templates of one [`gd::Template`] variant as is.
+macro_rules! template_categories {
+    ($($name:ident: $variant:ident($type:ty),)*) => {
+        $(
+            #[derive(Default)]
+            pub(crate) struct $name;
+            impl TableItemCategory for $name {
+                type Item = &'static $type;
 
-    fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        Some(templates().filter_map(|x| match x {
-            gd::Template::GameObject(x) => Some(x),
-            _ => None,
-        }))
-    }
+                fn source() -> Option<impl Iterator<Item = Self::Item>> {
+                    Some(templates()?.filter_map(|x| match x {
+                        gd::Template::$variant(x) => Some(x),
+                        _ => None,
+                    }))
+                }
+            }
+        )*
+    };
 }
 
-#[derive(Default)]
-pub(crate) struct SceneryCategory;
-impl TableItemCategory for SceneryCategory {
-    type Item = &'static SceneryTemplate;
-
-    fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        Some(templates().filter_map(|x| match x {
-            gd::Template::Scenery(x) => Some(x),
-            _ => None,
-        }))
-    }
+template_categories! {
+    GameObjectTemplateCategory: GameObject(GameObjectTemplate),
+    SceneryCategory: Scenery(SceneryTemplate),
+    TriggerCategory: Trigger(TriggerTemplate),
+    ProjectileCategory: Projectile(ProjectileTemplate),
+    SurfaceCategory: Surface(SurfaceTemplate),
+    PrefabCategory: Prefab(PrefabTemplate),
+    LightCategory: Light(LightTemplate),
+    LevelTemplateCategory: Level(LevelTemplate),
 }
 
 #[derive(GameObject)]
@@ -77,12 +88,16 @@ impl TableItemCategory for ItemCategory {
     type Item = Item;
 
     fn source() -> Option<impl Iterator<Item = Self::Item>> {
-        Some(templates().filter_map(|x| match x {
+        Some(templates()?.filter_map(|x| match x {
             gd::Template::Item(x) => Some(x.into()),
             _ => None,
         }))
     }
 
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.id.map(Into::into)
+    }
+
     fn draw_actions(&mut self, ui: &Ui, item: &mut Self::Item) {
         if let Some(id) = item.id {
             if !is_game_state_running().is_ok_and(|x| x) {
@@ -107,3 +122,80 @@ impl TableItemCategory for ItemCategory {
         }
     }
 }
+
+#[derive(GameObject)]
+pub(crate) struct Character {
+    pub template: &'static CharacterTemplate,
+    #[column(name = "GUID")]
+    pub id: Option<&'static str>,
+    #[column(name = "Internal Name", visible)]
+    pub name: &'static str,
+    #[column(name = "Display Name", visible)]
+    pub display_name: Option<&'static str>,
+    #[column(name = "Stats", visible)]
+    pub stats: Option<&'static str>,
+}
+
+impl From<&'static CharacterTemplate> for Character {
+    fn from(value: &'static CharacterTemplate) -> Self {
+        Self {
+            template: value,
+            id: value.id.get().map(|x| x.as_str()),
+            name: value.name.as_str(),
+            display_name: value.display_name.get().map(|x| x.as_str()),
+            stats: value.stats.get().map(|x| x.as_str()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct CharacterCategory;
+
+impl CharacterCategory {
+    fn draw_buttons(&self, ui: &Ui, id: &str) -> anyhow::Result<()> {
+        if ui.button("Spawn at host") {
+            match spawn_at_host(id)? {
+                Some(x) => info!("Spawned {x}"),
+                None => err!("failed to spawn {id}"),
+            }
+        }
+        ui.same_line();
+        if ui.button("Transform host into") {
+            transform_host(id)?;
+        }
+        Ok(())
+    }
+}
+
+impl TableItemCategory for CharacterCategory {
+    type Item = Character;
+
+    fn source() -> Option<impl Iterator<Item = Self::Item>> {
+        Some(templates()?.filter_map(|x| match x {
+            gd::Template::Character(x) => Some(x.into()),
+            _ => None,
+        }))
+    }
+
+    fn console_value(&self, item: &Self::Item) -> Option<Value> {
+        item.id.map(Into::into)
+    }
+
+    fn draw_actions(&mut self, ui: &Ui, item: &mut Self::Item) {
+        if let Some(id) = item.id {
+            if !is_game_state_running().is_ok_and(|x| x) {
+                ui.disabled(true, || {
+                    ui.text("Waiting for game to load...");
+                    ui.button("Spawn at host");
+                    ui.same_line();
+                    ui.button("Transform host into");
+                });
+                return;
+            }
+
+            if let Err(e) = self.draw_buttons(ui, id) {
+                err!("failed to spawn character: {e}");
+            }
+        }
+    }
+}
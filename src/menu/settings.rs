@@ -0,0 +1,195 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::{HMODULE, MAX_PATH},
+        System::LibraryLoader::{
+            GetModuleFileNameW, GetModuleHandleExW, GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS,
+            GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+        },
+    },
+};
+
+use crate::{
+    err, info,
+    log::{LogLevel, DEFAULT_LOG_CAPACITY},
+    wrappers::trace::DEFAULT_TRACE_CAPACITY,
+};
+
+const SETTINGS_FILE: &str = "bg3-debug-tool.json";
+const IMGUI_INI_FILE: &str = "bg3-debug-tool.ini";
+
+/// Everything about the menu that outlives a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub hotkeys: Hotkeys,
+    pub search: SearchSettings,
+    pub console: ConsoleSettings,
+    pub trace: TraceSettings,
+    pub debugger: DebuggerSettings,
+    pub log: LogSettings,
+}
+
+impl Settings {
+    /// Reads the settings file, falling back to the defaults if it doesn't
+    /// exist or can't be parsed.
+    pub fn load() -> Self {
+        let Some(path) = dll_dir().map(|x| x.join(SETTINGS_FILE)) else {
+            return Self::default();
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        match serde_json::from_str(&text) {
+            Ok(settings) => {
+                info!("Loaded settings from {}", path.display());
+                settings
+            }
+            Err(x) => {
+                err!("failed to load settings from {}: {x}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = dll_dir().map(|x| x.join(SETTINGS_FILE)) else {
+            anyhow::bail!("unable to locate the debug tool's directory");
+        };
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Window layout is left to imgui, stored alongside the settings file.
+    pub fn imgui_ini_path() -> Option<PathBuf> {
+        dll_dir().map(|x| x.join(IMGUI_INI_FILE))
+    }
+}
+
+/// Hotkeys are stored by their [`imgui::Key`] name, e.g. `F11`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Hotkeys {
+    pub toggle_menu: String,
+    pub toggle_tip: String,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Self { toggle_menu: "F11".into(), toggle_tip: "F9".into() }
+    }
+}
+
+impl Hotkeys {
+    pub fn key(name: &str) -> Option<imgui::Key> {
+        imgui::Key::VARIANTS.into_iter().find(|x| format!("{x:?}").eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SearchSettings {
+    /// Name of the selected category
+    pub category: String,
+    pub case_sensitive: bool,
+    /// Per category table settings, keyed by the category name
+    pub tables: BTreeMap<String, TableSettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TableSettings {
+    pub items_per_page: usize,
+    /// Keyed by the column name
+    pub columns: BTreeMap<String, ColumnSettings>,
+    /// Category specific options, e.g. the Osiris function type filters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<serde_json::Value>,
+}
+
+impl Default for TableSettings {
+    fn default() -> Self {
+        Self { items_per_page: 1000, columns: BTreeMap::new(), options: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ColumnSettings {
+    pub visible: bool,
+    pub included_in_search: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ConsoleSettings {
+    /// Most recent first
+    pub history: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TraceSettings {
+    /// Comma separated, as typed in the Trace tab
+    pub include: String,
+    pub exclude: String,
+    pub capacity: usize,
+}
+
+impl Default for TraceSettings {
+    fn default() -> Self {
+        Self { include: String::new(), exclude: String::new(), capacity: DEFAULT_TRACE_CAPACITY }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct LogSettings {
+    /// Lowest level shown in the Log tab
+    pub level: LogLevel,
+    pub filter: String,
+    pub capacity: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self { level: LogLevel::Info, filter: String::new(), capacity: DEFAULT_LOG_CAPACITY }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DebuggerSettings {
+    pub breakpoints: Vec<BreakpointSettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BreakpointSettings {
+    pub name: String,
+    #[serde(default)]
+    pub condition: String,
+    pub enabled: bool,
+}
+
+/// Directory the debug tool's DLL was loaded from.
+pub(crate) fn dll_dir() -> Option<PathBuf> {
+    unsafe {
+        let mut module = HMODULE::default();
+        GetModuleHandleExW(
+            GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+            PCWSTR(dll_dir as *const u16),
+            &mut module,
+        )
+        .ok()?;
+
+        let mut buf = [0; MAX_PATH as usize];
+        let len = GetModuleFileNameW(module, &mut buf) as usize;
+        if len == 0 {
+            return None;
+        }
+        PathBuf::from(String::from_utf16_lossy(&buf[..len])).parent().map(Into::into)
+    }
+}
@@ -0,0 +1,140 @@
+use imgui::{ListClipper, TableColumnSetup, TableFlags, Ui};
+
+use super::{search::export::ExportFormat, settings::TraceSettings};
+use crate::{
+    globals::Globals,
+    wrappers::trace::{parse_filters, DEFAULT_TRACE_CAPACITY},
+};
+
+const TRACE_FILE_NAME: &str = "osiris_trace";
+
+pub(crate) struct Trace {
+    include: String,
+    exclude: String,
+    capacity: i32,
+    auto_scroll: bool,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self {
+            include: String::new(),
+            exclude: String::new(),
+            capacity: DEFAULT_TRACE_CAPACITY as _,
+            auto_scroll: true,
+        }
+    }
+}
+
+impl Trace {
+    pub fn render(&mut self, ui: &Ui) {
+        let mut tracer = Globals::trace();
+
+        let paused = tracer.is_paused();
+        if ui.button(if paused { "Record" } else { "Pause" }) {
+            tracer.set_paused(!paused);
+        }
+        ui.same_line();
+        if ui.button("Clear") {
+            tracer.clear();
+        }
+        ui.same_line();
+        ui.checkbox("Auto-scroll", &mut self.auto_scroll);
+        ui.same_line();
+        ui.text(format!("{} of {} entries", tracer.entries.len(), tracer.capacity));
+
+        ui.set_next_item_width(ui.current_font_size() * 8.0);
+        if ui.input_int("Capacity", &mut self.capacity).build() {
+            self.capacity = self.capacity.max(1);
+            tracer.capacity = self.capacity as _;
+        }
+        if ui.input_text("Include", &mut self.include).build() {
+            tracer.include = parse_filters(&self.include);
+        }
+        if ui.input_text("Exclude", &mut self.exclude).build() {
+            tracer.exclude = parse_filters(&self.exclude);
+        }
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip_text(
+                "Comma separated function names or types, e.g. 'Event, ApplyStatus'.\nNames match \
+                 by substring, types (Event, Call, Query, Procedure, ...) exactly.\nFilters apply \
+                 to newly recorded entries.",
+            );
+        }
+
+        if !tracer.entries.is_empty() {
+            ui.text("Export trace as");
+            for format in ExportFormat::ALL {
+                ui.same_line();
+                if ui.button(format.name()) {
+                    let rows = tracer.entries.iter().map(|x| x.export()).collect();
+                    super::export(
+                        &format!("{TRACE_FILE_NAME}.{}", format.extension()),
+                        format.export_values("OsirisTrace", rows),
+                    );
+                }
+            }
+        }
+
+        let Some(_tbl) = ui.begin_table_with_sizing(
+            "trace-tbl",
+            5,
+            TableFlags::SCROLL_Y | TableFlags::RESIZABLE | TableFlags::ROW_BG,
+            [0.0, -1.0],
+            0.0,
+        ) else {
+            return;
+        };
+        ui.table_setup_scroll_freeze(0, 1);
+        for name in ["Time", "Kind", "Function", "Arguments", "Result"] {
+            ui.table_setup_column_with(TableColumnSetup::new(name));
+        }
+        ui.table_headers_row();
+
+        let mut clipper = ListClipper::new(tracer.entries.len() as _).begin(ui);
+        while clipper.step() {
+            for i in clipper.display_start()..clipper.display_end() {
+                let entry = &tracer.entries[i as usize];
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(format!("{:.3}", entry.time.as_secs_f64()));
+                ui.table_next_column();
+                ui.text(entry.kind.to_string());
+                ui.table_next_column();
+                ui.text(&entry.name);
+                ui.table_next_column();
+                ui.text(entry.args_str());
+                ui.table_next_column();
+                if entry.result {
+                    ui.text("true");
+                } else {
+                    ui.text_colored([1.0, 0.3, 0.3, 1.0], "false");
+                }
+            }
+        }
+        if self.auto_scroll && !tracer.is_paused() && ui.scroll_y() >= ui.scroll_max_y() {
+            ui.set_scroll_here_y_with_ratio(1.0);
+        }
+    }
+
+    pub fn settings(&self) -> TraceSettings {
+        TraceSettings {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            capacity: self.capacity as _,
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &TraceSettings) {
+        self.include.clone_from(&settings.include);
+        self.exclude.clone_from(&settings.exclude);
+        self.capacity = settings.capacity.max(1) as _;
+
+        let mut tracer = Globals::trace();
+        tracer.include = parse_filters(&self.include);
+        tracer.exclude = parse_filters(&self.exclude);
+        tracer.capacity = self.capacity as _;
+    }
+}
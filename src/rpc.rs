@@ -0,0 +1,491 @@
+//! Line delimited JSON-RPC 2.0, served on the `--port` socket so editors and
+//! test harnesses can drive the game.
+//!
+//! Each request is a single line and is answered with a single line carrying
+//! the same id, e.g.
+//!
+//! ```text
+//! {"jsonrpc": "2.0", "id": 1, "method": "osiris", "params": {"function": "GetHostCharacter"}}
+//! {"jsonrpc": "2.0", "id": 1, "result": "S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c"}
+//! ```
+//!
+//! Subscribed streams are sent as `log` and `trace` notifications, without an
+//! id. [`Server`] only deals with the protocol, the game is behind
+//! [`Backend`].
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value as JsonValue};
+
+pub(crate) mod game;
+
+/// Results returned by `search` when the request has no limit
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The backend failed to run a valid request
+const SERVER_ERROR: i64 = -32000;
+
+/// What the requests run against.
+pub(crate) trait Backend {
+    /// Calls the Osiris call or query `function`, returning the query's
+    /// result or null.
+    fn osiris(&mut self, function: &str, args: &[JsonValue]) -> anyhow::Result<JsonValue>;
+    /// Every field of the first `limit` objects of `category` matching the
+    /// Game Data Explorer query `query`.
+    fn search(
+        &mut self,
+        category: &str,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<JsonValue>>;
+    /// Every field of the object of `category` identified by `id`.
+    fn object(&mut self, category: &str, id: &str) -> anyhow::Result<Option<JsonValue>>;
+    fn symbols(&mut self) -> anyhow::Result<JsonValue>;
+    /// Names of the categories `search` and `object` accept.
+    fn categories(&mut self) -> Vec<String>;
+    /// Log lines of the records written after the first `recorded` ones,
+    /// and the new count.
+    fn log(&mut self, recorded: usize) -> (String, usize);
+    /// Trace entries recorded after the first `recorded` ones, and the new
+    /// count.
+    fn trace(&mut self, recorded: usize) -> (Vec<JsonValue>, usize);
+    /// Called when a connection subscribes to the trace or stops, the trace
+    /// is recorded while any is subscribed.
+    fn subscribe_trace(&mut self, subscribed: bool);
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Requests without an id are notifications and get no response
+    #[serde(default)]
+    id: Option<JsonValue>,
+    method: String,
+    #[serde(default)]
+    params: Option<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsirisParams {
+    function: String,
+    #[serde(default)]
+    args: Vec<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    category: String,
+    #[serde(default)]
+    query: String,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjectParams {
+    category: String,
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscribeParams {
+    streams: Vec<Stream>,
+    /// Also send what was recorded before subscribing
+    #[serde(default)]
+    backlog: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Stream {
+    Log,
+    Trace,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self { code, message: message.to_string() }
+    }
+}
+
+/// One connection's protocol state, the streams it subscribed to and how far
+/// they were sent.
+#[derive(Debug)]
+pub(crate) struct Server<B> {
+    backend: B,
+    log: Option<usize>,
+    trace: Option<usize>,
+}
+
+impl<B: Backend> Server<B> {
+    pub const fn new(backend: B) -> Self {
+        Self { backend, log: None, trace: None }
+    }
+
+    /// Handles a request line, returning the response line unless the
+    /// request is a notification.
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let request = match serde_json::from_str::<JsonValue>(line) {
+            Ok(x) => x,
+            Err(x) => return Some(response(JsonValue::Null, Err(RpcError::new(PARSE_ERROR, x)))),
+        };
+        let request = match Request::deserialize(request) {
+            Ok(x) => x,
+            Err(x) => {
+                return Some(response(JsonValue::Null, Err(RpcError::new(INVALID_REQUEST, x))));
+            }
+        };
+
+        let result = self.dispatch(&request.method, request.params.unwrap_or(JsonValue::Null));
+        request.id.map(|id| response(id, result))
+    }
+
+    /// Drops the subscriptions, called once the connection is closed.
+    pub fn close(&mut self) {
+        self.log = None;
+        self.unsubscribe_trace();
+    }
+
+    fn unsubscribe_trace(&mut self) {
+        if self.trace.take().is_some() {
+            self.backend.subscribe_trace(false);
+        }
+    }
+
+    /// Notifications with what the subscribed streams recorded since the
+    /// previous poll.
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut notifications = Vec::new();
+        if let Some(recorded) = self.log {
+            let (text, recorded) = self.backend.log(recorded);
+            self.log = Some(recorded);
+            if !text.is_empty() {
+                notifications.push(notification("log", json!({ "text": text })));
+            }
+        }
+        if let Some(recorded) = self.trace {
+            let (entries, recorded) = self.backend.trace(recorded);
+            self.trace = Some(recorded);
+            if !entries.is_empty() {
+                notifications.push(notification("trace", json!({ "entries": entries })));
+            }
+        }
+        notifications
+    }
+
+    fn dispatch(&mut self, method: &str, params: JsonValue) -> Result<JsonValue, RpcError> {
+        let server_error = |x: anyhow::Error| RpcError::new(SERVER_ERROR, format!("{x:#}"));
+        match method {
+            "osiris" => {
+                let params = parse_params::<OsirisParams>(params)?;
+                self.backend.osiris(&params.function, &params.args).map_err(server_error)
+            }
+            "search" => {
+                let params = parse_params::<SearchParams>(params)?;
+                let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+                let objects = self
+                    .backend
+                    .search(&params.category, &params.query, limit)
+                    .map_err(server_error)?;
+                Ok(JsonValue::Array(objects))
+            }
+            "object" => {
+                let params = parse_params::<ObjectParams>(params)?;
+                self.backend.object(&params.category, &params.id).map_err(server_error)?.ok_or_else(
+                    || {
+                        RpcError::new(
+                            SERVER_ERROR,
+                            format!("no {} '{}'", params.category, params.id),
+                        )
+                    },
+                )
+            }
+            "symbols" => self.backend.symbols().map_err(server_error),
+            "categories" => Ok(json!(self.backend.categories())),
+            "subscribe" => {
+                let params = parse_params::<SubscribeParams>(params)?;
+                // Past the end, the backend answers with the current position
+                for stream in params.streams {
+                    match stream {
+                        Stream::Log if params.backlog => self.log = Some(0),
+                        Stream::Log => self.log = Some(self.backend.log(usize::MAX).1),
+                        Stream::Trace => {
+                            if self.trace.is_none() {
+                                self.backend.subscribe_trace(true);
+                            }
+                            let start =
+                                if params.backlog { 0 } else { self.backend.trace(usize::MAX).1 };
+                            self.trace = Some(start);
+                        }
+                    }
+                }
+                Ok(JsonValue::Bool(true))
+            }
+            "unsubscribe" => {
+                let params = parse_params::<SubscribeParams>(params)?;
+                for stream in params.streams {
+                    match stream {
+                        Stream::Log => self.log = None,
+                        Stream::Trace => self.unsubscribe_trace(),
+                    }
+                }
+                Ok(JsonValue::Bool(true))
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{method}'"))),
+        }
+    }
+}
+
+/// Missing params are treated as an empty object, so methods without
+/// required params can omit them.
+fn parse_params<T: DeserializeOwned>(params: JsonValue) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    T::deserialize(params).map_err(|x| RpcError::new(INVALID_PARAMS, x))
+}
+
+fn response(id: JsonValue, result: Result<JsonValue, RpcError>) -> String {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(x) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": x.code, "message": x.message },
+        }),
+    }
+    .to_string()
+}
+
+fn notification(method: &str, params: JsonValue) -> String {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers `osiris` with its arguments and records the streams in memory.
+    #[derive(Debug, Default)]
+    struct MockBackend {
+        log: Vec<String>,
+        trace: Vec<JsonValue>,
+        trace_subscribers: usize,
+    }
+
+    impl Backend for MockBackend {
+        fn osiris(&mut self, function: &str, args: &[JsonValue]) -> anyhow::Result<JsonValue> {
+            match function {
+                "Fail" => anyhow::bail!("query failed"),
+                _ => Ok(json!({ "function": function, "args": args })),
+            }
+        }
+
+        fn search(
+            &mut self,
+            category: &str,
+            query: &str,
+            limit: usize,
+        ) -> anyhow::Result<Vec<JsonValue>> {
+            Ok(vec![json!({ "category": category, "query": query, "limit": limit })])
+        }
+
+        fn object(&mut self, _category: &str, id: &str) -> anyhow::Result<Option<JsonValue>> {
+            Ok((id == "known").then(|| json!({ "id": id })))
+        }
+
+        fn symbols(&mut self) -> anyhow::Result<JsonValue> {
+            Ok(json!([]))
+        }
+
+        fn categories(&mut self) -> Vec<String> {
+            vec!["Spells".into(), "Osiris Functions".into()]
+        }
+
+        fn log(&mut self, recorded: usize) -> (String, usize) {
+            let new = &self.log[recorded.min(self.log.len())..];
+            (new.iter().map(|x| format!("{x}\n")).collect(), self.log.len())
+        }
+
+        fn trace(&mut self, recorded: usize) -> (Vec<JsonValue>, usize) {
+            (self.trace[recorded.min(self.trace.len())..].to_vec(), self.trace.len())
+        }
+
+        fn subscribe_trace(&mut self, subscribed: bool) {
+            if subscribed {
+                self.trace_subscribers += 1;
+            } else {
+                self.trace_subscribers -= 1;
+            }
+        }
+    }
+
+    fn handle(server: &mut Server<MockBackend>, line: &str) -> Option<JsonValue> {
+        server.handle(line).map(|x| serde_json::from_str(&x).unwrap())
+    }
+
+    fn error_code(server: &mut Server<MockBackend>, line: &str) -> JsonValue {
+        handle(server, line).unwrap()["error"]["code"].clone()
+    }
+
+    fn poll(server: &mut Server<MockBackend>) -> Vec<JsonValue> {
+        server.poll().iter().map(|x| serde_json::from_str(x).unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_error() {
+        let mut server = Server::new(MockBackend::default());
+        let response = handle(&mut server, r#"{"id": 1, "method": "#).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], JsonValue::Null);
+        assert_eq!(error_code(&mut server, r#"{"id": 1}"#), INVALID_REQUEST);
+    }
+
+    #[test]
+    fn unknown_method() {
+        let mut server = Server::new(MockBackend::default());
+        let code = error_code(&mut server, r#"{"jsonrpc": "2.0", "id": 1, "method": "nope"}"#);
+        assert_eq!(code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let mut server = Server::new(MockBackend::default());
+        for line in [
+            r#"{"id": 1, "method": "search"}"#,
+            r#"{"id": 1, "method": "search", "params": {"category": "Spells", "limit": "x"}}"#,
+            r#"{"id": 1, "method": "osiris", "params": [1, 2]}"#,
+            r#"{"id": 1, "method": "subscribe", "params": {"streams": ["stdout"]}}"#,
+        ] {
+            assert_eq!(error_code(&mut server, line), INVALID_PARAMS, "{line}");
+        }
+        let line = r#"{"id": 1, "method": "osiris", "params": {"function": "Fail"}}"#;
+        assert_eq!(error_code(&mut server, line), SERVER_ERROR);
+    }
+
+    #[test]
+    fn notification_has_no_response() {
+        let mut server = Server::new(MockBackend::default());
+        assert!(handle(&mut server, r#"{"jsonrpc": "2.0", "method": "symbols"}"#).is_none());
+        assert!(handle(&mut server, r#"{"method": "nope"}"#).is_none());
+    }
+
+    #[test]
+    fn id_is_echoed() {
+        let mut server = Server::new(MockBackend::default());
+        for id in [json!(7), json!("abc")] {
+            let line = json!({ "jsonrpc": "2.0", "id": id, "method": "osiris", "params": {
+                "function": "GetHostCharacter",
+            }});
+            let response = handle(&mut server, &line.to_string()).unwrap();
+            assert_eq!(response["id"], id);
+            assert_eq!(response["result"]["function"], "GetHostCharacter");
+        }
+
+        let line = r#"{"id": "c", "method": "categories"}"#;
+        let response = handle(&mut server, line).unwrap();
+        assert_eq!(response["result"], json!(["Spells", "Osiris Functions"]));
+
+        let line = r#"{"id": 3, "method": "search", "params": {"category": "Spells"}}"#;
+        let response = handle(&mut server, line).unwrap();
+        assert_eq!(response["id"], 3);
+        assert_eq!(response["result"][0]["limit"], DEFAULT_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn subscribe_sends_new_entries() {
+        let backend = MockBackend {
+            log: vec!["old".into()],
+            trace: vec![json!("old")],
+            ..Default::default()
+        };
+        let mut server = Server::new(backend);
+        let line = r#"{"id": 1, "method": "subscribe", "params": {"streams": ["log", "trace"]}}"#;
+        assert_eq!(handle(&mut server, line).unwrap()["result"], true);
+        assert!(poll(&mut server).is_empty());
+
+        server.backend.log.push("new".into());
+        server.backend.trace.push(json!("new"));
+        let notifications = poll(&mut server);
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0]["method"], "log");
+        assert_eq!(notifications[0]["params"]["text"], "new\n");
+        assert_eq!(notifications[1]["method"], "trace");
+        assert_eq!(notifications[1]["params"]["entries"], json!(["new"]));
+        assert!(notifications.iter().all(|x| x.get("id").is_none()));
+        assert!(poll(&mut server).is_empty());
+    }
+
+    #[test]
+    fn subscribe_with_backlog() {
+        let backend = MockBackend {
+            log: vec!["old".into()],
+            trace: vec![json!("old")],
+            ..Default::default()
+        };
+        let mut server = Server::new(backend);
+        let line =
+            r#"{"id": 1, "method": "subscribe", "params": {"streams": ["log"], "backlog": true}}"#;
+        handle(&mut server, line);
+
+        let notifications = poll(&mut server);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["params"]["text"], "old\n");
+        assert!(poll(&mut server).is_empty());
+
+        server.backend.log.push("new".into());
+        assert_eq!(poll(&mut server)[0]["params"]["text"], "new\n");
+    }
+
+    #[test]
+    fn unsubscribe_stops_streams() {
+        let mut server = Server::new(MockBackend::default());
+        handle(
+            &mut server,
+            r#"{"id": 1, "method": "subscribe", "params": {"streams": ["log", "trace"]}}"#,
+        );
+        let line = r#"{"id": 2, "method": "unsubscribe", "params": {"streams": ["log"]}}"#;
+        assert_eq!(handle(&mut server, line).unwrap()["result"], true);
+
+        server.backend.log.push("new".into());
+        server.backend.trace.push(json!("new"));
+        let notifications = poll(&mut server);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["method"], "trace");
+
+        handle(
+            &mut server,
+            r#"{"id": 3, "method": "unsubscribe", "params": {"streams": ["trace"]}}"#,
+        );
+        server.backend.trace.push(json!("newer"));
+        assert!(poll(&mut server).is_empty());
+        assert_eq!(server.backend.trace_subscribers, 0);
+    }
+
+    #[test]
+    fn trace_is_recorded_while_subscribed() {
+        let mut server = Server::new(MockBackend::default());
+        let line = r#"{"id": 1, "method": "subscribe", "params": {"streams": ["trace"]}}"#;
+        handle(&mut server, line);
+        handle(&mut server, line);
+        assert_eq!(server.backend.trace_subscribers, 1);
+
+        let line = r#"{"id": 2, "method": "unsubscribe", "params": {"streams": ["trace"]}}"#;
+        handle(&mut server, line);
+        handle(&mut server, line);
+        assert_eq!(server.backend.trace_subscribers, 0);
+
+        handle(
+            &mut server,
+            r#"{"id": 3, "method": "subscribe", "params": {"streams": ["trace"]}}"#,
+        );
+        server.close();
+        assert_eq!(server.backend.trace_subscribers, 0);
+        assert!(poll(&mut server).is_empty());
+    }
+}
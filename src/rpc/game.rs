@@ -0,0 +1,212 @@
+use std::{
+    collections::VecDeque,
+    ffi::CString,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use serde_json::{json, Value as JsonValue};
+
+use super::{Backend, Server};
+use crate::{
+    err,
+    globals::Globals,
+    info,
+    menu::search::{categories, find_object, search_objects},
+    warn,
+    wrappers::osiris::{FunctionCall, Value},
+};
+
+/// Responses are written on the menu thread, a client that stops reading is
+/// dropped instead of stalling the game
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The remote connections, requests are queued by each socket's reader
+/// thread and run on the menu thread, like the console's.
+#[derive(Debug)]
+pub(crate) struct Connections {
+    clients: Vec<Client>,
+    next_id: usize,
+}
+
+impl Connections {
+    pub const fn new() -> Self {
+        Self { clients: Vec::new(), next_id: 0 }
+    }
+
+    fn client(&mut self, id: usize) -> Option<&mut Client> {
+        self.clients.iter_mut().find(|x| x.id == id)
+    }
+}
+
+impl Default for Connections {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct Client {
+    id: usize,
+    addr: SocketAddr,
+    pending: VecDeque<String>,
+    writer: TcpStream,
+    server: Server<GameBackend>,
+    /// Set by the reader thread once the client disconnected
+    closed: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct GameBackend;
+
+impl Backend for GameBackend {
+    fn osiris(&mut self, function: &str, args: &[JsonValue]) -> anyhow::Result<JsonValue> {
+        let args = args.iter().map(to_value).collect::<anyhow::Result<Vec<_>>>()?;
+        let result = FunctionCall { ident: function.into(), args }.call()?;
+        Ok(result.map_or(JsonValue::Null, from_value))
+    }
+
+    fn search(
+        &mut self,
+        category: &str,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<JsonValue>> {
+        Ok(search_objects(category, query, limit)?.into_iter().map(Into::into).collect())
+    }
+
+    fn object(&mut self, category: &str, id: &str) -> anyhow::Result<Option<JsonValue>> {
+        Ok(find_object(category, id)?.map(Into::into))
+    }
+
+    fn symbols(&mut self) -> anyhow::Result<JsonValue> {
+        Ok(serde_json::to_value(&Globals::symbol_report().entries)?)
+    }
+
+    fn categories(&mut self) -> Vec<String> {
+        categories().iter().map(|x| x.to_string()).collect()
+    }
+
+    fn log(&mut self, recorded: usize) -> (String, usize) {
+        let logger = Globals::log();
+        (logger.since(recorded).map(|x| format!("{x}\n")).collect(), logger.recorded)
+    }
+
+    fn trace(&mut self, recorded: usize) -> (Vec<JsonValue>, usize) {
+        let tracer = Globals::trace();
+        (tracer.since(recorded).map(|x| x.export().into()).collect(), tracer.recorded)
+    }
+
+    fn subscribe_trace(&mut self, subscribed: bool) {
+        Globals::trace().subscribe(subscribed);
+    }
+}
+
+/// Binds `addr` and accepts connections on a new thread for as long as the
+/// game runs, so clients can come and go. Returns the bound address.
+pub(crate) fn listen(addr: impl ToSocketAddrs) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if let Err(x) = stream.and_then(start) {
+                warn!("failed to accept a remote connection: {x}");
+            }
+        }
+    });
+    Ok(local_addr)
+}
+
+/// Registers `stream` and reads its requests on a new thread until it is
+/// closed.
+fn start(stream: TcpStream) -> io::Result<()> {
+    let addr = stream.peer_addr()?;
+    let reader = BufReader::new(stream.try_clone()?);
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+    let id = {
+        let mut connections = Globals::rpc();
+        let id = connections.next_id;
+        connections.next_id += 1;
+        connections.clients.push(Client {
+            id,
+            addr,
+            pending: VecDeque::new(),
+            writer: stream,
+            server: Server::new(GameBackend),
+            closed: false,
+        });
+        id
+    };
+    info!("Remote connection from {addr}");
+
+    std::thread::spawn(move || {
+        for line in reader.lines() {
+            match line {
+                Ok(x) if x.trim().is_empty() => (),
+                Ok(x) => match Globals::rpc().client(id) {
+                    Some(client) => client.pending.push_back(x),
+                    None => return,
+                },
+                Err(x) => {
+                    warn!("remote connection from {addr} failed: {x}");
+                    break;
+                }
+            }
+        }
+        if let Some(client) = Globals::rpc().client(id) {
+            client.closed = true;
+        }
+    });
+    Ok(())
+}
+
+/// Runs the queued requests and sends their responses, along with the
+/// subscribed streams. Called by the menu every frame.
+pub(crate) fn poll() {
+    let mut connections = Globals::rpc();
+    connections.clients.retain_mut(|client| {
+        let Client { addr, pending, writer, server, closed, .. } = client;
+        // A client that is done sending may still read the responses
+        let mut lines = pending.drain(..).filter_map(|x| server.handle(&x)).collect::<Vec<_>>();
+        lines.extend(server.poll());
+        for line in lines {
+            if let Err(x) = writeln!(writer, "{line}") {
+                err!("failed to send to the remote connection from {addr}, dropping it: {x}");
+                server.close();
+                return false;
+            }
+        }
+        if *closed {
+            info!("Remote connection from {addr} closed");
+            server.close();
+        }
+        !*closed
+    });
+}
+
+/// Numbers without a fraction are integers, `null` is Osiris' None.
+fn to_value(value: &JsonValue) -> anyhow::Result<Value> {
+    Ok(match value {
+        JsonValue::Null => Value::None,
+        JsonValue::Bool(x) => Value::Int(*x as _),
+        JsonValue::Number(x) => match x.as_i64() {
+            Some(x) => Value::Int(x),
+            None => Value::Float(x.as_f64().ok_or_else(|| anyhow!("invalid number {x}"))? as _),
+        },
+        JsonValue::String(x) => Value::String(CString::new(x.as_str())?),
+        _ => bail!("unsupported argument {value}, expected a number, string or null"),
+    })
+}
+
+fn from_value(value: Value) -> JsonValue {
+    match value {
+        Value::None => JsonValue::Null,
+        Value::Int(x) => json!(x),
+        Value::Float(x) => json!(x),
+        Value::String(x) => json!(x.to_string_lossy()),
+    }
+}
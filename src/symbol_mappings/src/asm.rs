@@ -0,0 +1,462 @@
+//! A minimal x86-64 instruction decoder. It only decodes as much as is needed
+//! to find the length of an instruction and the address referenced by its
+//! RIP-relative memory operand or relative branch.
+
+/// Opcode map an instruction's opcode byte belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpcodeMap {
+    /// One-byte opcodes
+    Primary,
+    /// `0F xx`
+    Secondary,
+    /// `0F 38 xx`
+    Map0F38,
+    /// `0F 3A xx`
+    Map0F3A,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Instruction {
+    /// Offset of the instruction in the decoded buffer
+    pub offset: usize,
+    pub len: usize,
+    pub map: OpcodeMap,
+    pub opcode: u8,
+    pub rex: Option<u8>,
+    pub modrm: Option<u8>,
+    /// Address referenced by a RIP-relative memory operand or a relative
+    /// branch, as an offset into the decoded buffer
+    pub target: Option<usize>,
+}
+
+impl Instruction {
+    pub fn rex_w(&self) -> bool {
+        self.rex.is_some_and(|x| x & 0x08 != 0)
+    }
+
+    /// The `reg` field of the ModRM byte.
+    pub fn reg(&self) -> Option<u8> {
+        self.modrm.map(|x| (x >> 3) & 7)
+    }
+
+    pub fn is_rip_relative(&self) -> bool {
+        self.modrm.is_some_and(|x| x & 0xC7 == 0x05)
+    }
+
+    /// Register to register form, e.g. `mov rax, rcx`.
+    pub fn is_register_direct(&self) -> bool {
+        self.modrm.is_some_and(|x| x >> 6 == 3)
+    }
+
+    pub fn is(&self, map: OpcodeMap, opcode: u8) -> bool {
+        self.map == map && self.opcode == opcode
+    }
+
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Imm {
+    None,
+    Byte,
+    Word,
+    /// 16-bit with an operand size override, 32-bit otherwise
+    Dword,
+    /// 64-bit with REX.W, 16-bit with an operand size override, 32-bit
+    /// otherwise
+    Qword,
+    /// `enter`
+    WordByte,
+    /// `mov` to or from a 64-bit absolute address
+    Moffs,
+    /// Group 3 `test` takes an immediate, the other members don't
+    Group3(u8),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Rel {
+    None,
+    Byte,
+    Dword,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Operands {
+    modrm: bool,
+    imm: Imm,
+    rel: Rel,
+}
+
+const fn ops(modrm: bool, imm: Imm) -> Option<Operands> {
+    Some(Operands { modrm, imm, rel: Rel::None })
+}
+
+const fn rel(rel: Rel) -> Option<Operands> {
+    Some(Operands { modrm: false, imm: Imm::None, rel })
+}
+
+fn primary(opcode: u8) -> Option<Operands> {
+    match opcode {
+        // ALU ops: add, or, adc, sbb, and, sub, xor, cmp
+        0x00..=0x3F => match opcode & 7 {
+            0..=3 => ops(true, Imm::None),
+            4 => ops(false, Imm::Byte),
+            5 => ops(false, Imm::Dword),
+            _ => None,
+        },
+        0x50..=0x5F => ops(false, Imm::None),
+        0x63 => ops(true, Imm::None),
+        0x68 => ops(false, Imm::Dword),
+        0x69 => ops(true, Imm::Dword),
+        0x6A => ops(false, Imm::Byte),
+        0x6B => ops(true, Imm::Byte),
+        0x6C..=0x6F => ops(false, Imm::None),
+        0x70..=0x7F => rel(Rel::Byte),
+        0x80 | 0x83 => ops(true, Imm::Byte),
+        0x81 => ops(true, Imm::Dword),
+        0x84..=0x8F => ops(true, Imm::None),
+        0x90..=0x99 | 0x9B..=0x9F => ops(false, Imm::None),
+        0xA0..=0xA3 => ops(false, Imm::Moffs),
+        0xA4..=0xA7 | 0xAA..=0xAF => ops(false, Imm::None),
+        0xA8 => ops(false, Imm::Byte),
+        0xA9 => ops(false, Imm::Dword),
+        0xB0..=0xB7 => ops(false, Imm::Byte),
+        0xB8..=0xBF => ops(false, Imm::Qword),
+        0xC0 | 0xC1 | 0xC6 => ops(true, Imm::Byte),
+        0xC2 | 0xCA => ops(false, Imm::Word),
+        0xC3 | 0xC9 | 0xCB | 0xCC | 0xCF => ops(false, Imm::None),
+        0xC7 => ops(true, Imm::Dword),
+        0xC8 => ops(false, Imm::WordByte),
+        0xCD => ops(false, Imm::Byte),
+        0xD0..=0xD3 | 0xD8..=0xDF => ops(true, Imm::None),
+        0xD7 => ops(false, Imm::None),
+        0xE0..=0xE3 | 0xEB => rel(Rel::Byte),
+        0xE4..=0xE7 => ops(false, Imm::Byte),
+        0xE8 | 0xE9 => rel(Rel::Dword),
+        0xEC..=0xEF | 0xF1 | 0xF4 | 0xF5 | 0xF8..=0xFD => ops(false, Imm::None),
+        0xF6 => ops(true, Imm::Group3(1)),
+        0xF7 => ops(true, Imm::Group3(4)),
+        0xFE | 0xFF => ops(true, Imm::None),
+        _ => None,
+    }
+}
+
+fn secondary(opcode: u8) -> Option<Operands> {
+    match opcode {
+        0x00..=0x03 | 0x0D | 0x10..=0x1F | 0x20..=0x23 | 0x28..=0x2F => ops(true, Imm::None),
+        0x05..=0x09 | 0x0B | 0x0E | 0x30..=0x37 | 0x77 => ops(false, Imm::None),
+        // 3DNow! has its opcode in the immediate position
+        0x0F => ops(true, Imm::Byte),
+        0x40..=0x6F | 0x74..=0x76 | 0x78..=0x7F => ops(true, Imm::None),
+        0x70..=0x73 => ops(true, Imm::Byte),
+        0x80..=0x8F => rel(Rel::Dword),
+        0x90..=0x9F => ops(true, Imm::None),
+        0xA0..=0xA2 | 0xA8..=0xAA | 0xC8..=0xCF => ops(false, Imm::None),
+        0xA3 | 0xA5 | 0xAB | 0xAD..=0xB9 | 0xBB..=0xC1 | 0xC3 | 0xC7 => ops(true, Imm::None),
+        0xA4 | 0xAC | 0xBA | 0xC2 | 0xC4..=0xC6 => ops(true, Imm::Byte),
+        0xD0..=0xFF => ops(true, Imm::None),
+        _ => None,
+    }
+}
+
+fn operands(map: OpcodeMap, opcode: u8) -> Option<Operands> {
+    match map {
+        OpcodeMap::Primary => primary(opcode),
+        OpcodeMap::Secondary => secondary(opcode),
+        OpcodeMap::Map0F38 => ops(true, Imm::None),
+        OpcodeMap::Map0F3A => ops(true, Imm::Byte),
+    }
+}
+
+/// Decodes the instruction at offset `insn` of `mem`. Returns `None` if the
+/// bytes don't form a valid 64-bit mode instruction or the instruction runs
+/// past the end of `mem`.
+pub fn decode(mem: &[u8], insn: usize) -> Option<Instruction> {
+    let byte = |i: usize| mem.get(insn + i).copied();
+
+    let mut i = 0;
+    let mut operand_size_override = false;
+    let mut address_size_override = false;
+    let mut rex = None;
+
+    // Legacy prefixes, a REX prefix is only meaningful right before the opcode
+    loop {
+        match byte(i)? {
+            0x66 => operand_size_override = true,
+            0x67 => address_size_override = true,
+            0xF0 | 0xF2 | 0xF3 | 0x26 | 0x2E | 0x36 | 0x3E | 0x64 | 0x65 => (),
+            0x40..=0x4F => {
+                rex = byte(i);
+                i += 1;
+                break;
+            }
+            _ => break,
+        }
+        i += 1;
+        if i > 14 {
+            return None;
+        }
+    }
+
+    let (map, opcode) = match byte(i)? {
+        // VEX, C4 and C5 are always VEX in 64-bit mode
+        0xC5 => {
+            i += 3;
+            (OpcodeMap::Secondary, byte(i - 1)?)
+        }
+        0xC4 => {
+            let map = vex_map(byte(i + 1)? & 0x1F)?;
+            rex = Some(0x40 | ((byte(i + 2)? >> 4) & 0x08));
+            i += 4;
+            (map, byte(i - 1)?)
+        }
+        // EVEX
+        0x62 => {
+            let map = vex_map(byte(i + 1)? & 0x07)?;
+            rex = Some(0x40 | ((byte(i + 2)? >> 4) & 0x08));
+            i += 5;
+            (map, byte(i - 1)?)
+        }
+        0x0F => match byte(i + 1)? {
+            0x38 => {
+                i += 3;
+                (OpcodeMap::Map0F38, byte(i - 1)?)
+            }
+            0x3A => {
+                i += 3;
+                (OpcodeMap::Map0F3A, byte(i - 1)?)
+            }
+            x => {
+                i += 2;
+                (OpcodeMap::Secondary, x)
+            }
+        },
+        x => {
+            i += 1;
+            (OpcodeMap::Primary, x)
+        }
+    };
+
+    let ops = operands(map, opcode)?;
+    let rex_w = rex.is_some_and(|x| x & 0x08 != 0);
+    let mut modrm = None;
+    let mut rip_disp = None;
+
+    if ops.modrm {
+        let m = byte(i)?;
+        modrm = Some(m);
+        i += 1;
+
+        let (mode, rm) = (m >> 6, m & 7);
+        if mode != 3 && rm == 4 {
+            let sib = byte(i)?;
+            i += 1;
+            if mode == 0 && sib & 7 == 5 {
+                i += 4;
+            }
+        }
+        match mode {
+            0 if rm == 5 => {
+                rip_disp = Some(i);
+                i += 4;
+            }
+            1 => i += 1,
+            2 => i += 4,
+            _ => (),
+        }
+    }
+
+    let imm_len = match ops.imm {
+        Imm::None => 0,
+        Imm::Byte => 1,
+        Imm::Word => 2,
+        Imm::Dword if operand_size_override && !rex_w => 2,
+        Imm::Dword => 4,
+        Imm::Qword if rex_w => 8,
+        Imm::Qword if operand_size_override => 2,
+        Imm::Qword => 4,
+        Imm::WordByte => 3,
+        Imm::Moffs if address_size_override => 4,
+        Imm::Moffs => 8,
+        Imm::Group3(len) => match modrm.map(|x| (x >> 3) & 7) {
+            Some(0 | 1) if len == 4 && operand_size_override && !rex_w => 2,
+            Some(0 | 1) => len as usize,
+            _ => 0,
+        },
+    };
+    i += imm_len;
+
+    let rel_at = i;
+    i += match ops.rel {
+        Rel::None => 0,
+        Rel::Byte => 1,
+        Rel::Dword => 4,
+    };
+
+    // Make sure the whole instruction is in bounds
+    byte(i - 1)?;
+    let len = i;
+
+    let read_i32 = |at: usize| -> Option<isize> {
+        let bytes = mem.get(insn + at..insn + at + 4)?;
+        Some(i32::from_le_bytes(bytes.try_into().ok()?) as isize)
+    };
+    let target = match (rip_disp, ops.rel) {
+        (Some(at), _) => read_i32(at),
+        (None, Rel::Byte) => Some(byte(rel_at)? as i8 as isize),
+        (None, Rel::Dword) => read_i32(rel_at),
+        (None, Rel::None) => None,
+    }
+    .and_then(|x| (insn + len).checked_add_signed(x));
+
+    Some(Instruction { offset: insn, len, map, opcode, rex, modrm, target })
+}
+
+fn vex_map(map: u8) -> Option<OpcodeMap> {
+    match map {
+        1 => Some(OpcodeMap::Secondary),
+        2 => Some(OpcodeMap::Map0F38),
+        3 => Some(OpcodeMap::Map0F3A),
+        _ => None,
+    }
+}
+
+/// Resolves the address referenced by the instruction at offset `insn` of
+/// `mem` and returns it as an offset into `mem`.
+pub fn resolve_instruction_ref(mem: &[u8], insn: usize) -> Option<usize> {
+    decode(mem, insn)?.target
+}
+
+/// Decodes instructions one after another, starting at offset `start` of
+/// `mem`. Stops at the first byte sequence that can't be decoded.
+pub fn instructions(mem: &[u8], start: usize) -> impl Iterator<Item = Instruction> + '_ {
+    let mut next = Some(start);
+    std::iter::from_fn(move || {
+        let insn = decode(mem, next?);
+        next = insn.map(|x| x.end());
+        insn
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes `bytes` placed at `offset` of an int3 filled buffer and checks
+    /// the length, target and whether the operand is RIP-relative.
+    #[track_caller]
+    fn check(offset: usize, bytes: &[u8], len: usize, target: Option<usize>, rip: bool) {
+        let mut mem = vec![0xCC; offset];
+        mem.extend_from_slice(bytes);
+        let insn = decode(&mem, offset).expect("failed to decode");
+        assert_eq!(insn.len, len, "len of {bytes:02X?}");
+        assert_eq!(insn.target, target, "target of {bytes:02X?}");
+        assert_eq!(insn.is_rip_relative(), rip, "is_rip_relative of {bytes:02X?}");
+    }
+
+    #[test]
+    fn relative_branches() {
+        // call +0x10
+        check(0, &[0xE8, 0x10, 0x00, 0x00, 0x00], 5, Some(0x15), false);
+        // jmp -0x15
+        check(0x10, &[0xE9, 0xEB, 0xFF, 0xFF, 0xFF], 5, Some(0x00), false);
+        // jz rel8, jnz rel32
+        check(0, &[0x74, 0x05], 2, Some(0x07), false);
+        check(0, &[0x0F, 0x85, 0x00, 0x01, 0x00, 0x00], 6, Some(0x106), false);
+    }
+
+    #[test]
+    fn rip_relative_rex_w() {
+        // mov rax, [rip+0x10]
+        check(0, &[0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00], 7, Some(0x17), true);
+        // mov [rip-0x10], rcx
+        check(0x20, &[0x48, 0x89, 0x0D, 0xF0, 0xFF, 0xFF, 0xFF], 7, Some(0x17), true);
+        // lea rdx, [rip+0x100]
+        check(0, &[0x48, 0x8D, 0x15, 0x00, 0x01, 0x00, 0x00], 7, Some(0x107), true);
+        // mov rax, [rcx+8]
+        check(0, &[0x48, 0x8B, 0x41, 0x08], 4, None, false);
+        // mov rax, [rsp+0x100]
+        check(0, &[0x48, 0x8B, 0x84, 0x24, 0x00, 0x01, 0x00, 0x00], 8, None, false);
+    }
+
+    #[test]
+    fn legacy_prefixes() {
+        // mov [rip+4], ax
+        check(0, &[0x66, 0x89, 0x05, 0x04, 0x00, 0x00, 0x00], 7, Some(0x0B), true);
+        // mov word ptr [rip+0x10], 0x1234
+        check(0, &[0x66, 0xC7, 0x05, 0x10, 0x00, 0x00, 0x00, 0x34, 0x12], 9, Some(0x19), true);
+        // movsd xmm0, [rip+8]
+        check(0, &[0xF2, 0x0F, 0x10, 0x05, 0x08, 0x00, 0x00, 0x00], 8, Some(0x10), true);
+        // movss xmm1, [rip]
+        check(0, &[0xF3, 0x0F, 0x10, 0x0D, 0x00, 0x00, 0x00, 0x00], 8, Some(0x08), true);
+        // popcnt rax, rcx
+        check(0, &[0xF3, 0x48, 0x0F, 0xB8, 0xC1], 5, None, false);
+        // mov ax, 0x1234
+        check(0, &[0x66, 0xB8, 0x34, 0x12], 4, None, false);
+    }
+
+    #[test]
+    fn movzx() {
+        // movzx eax, byte ptr [rip+0x10]
+        check(0, &[0x0F, 0xB6, 0x05, 0x10, 0x00, 0x00, 0x00], 7, Some(0x17), true);
+        // movzx rcx, word ptr [rip+0x20]
+        check(0, &[0x48, 0x0F, 0xB7, 0x0D, 0x20, 0x00, 0x00, 0x00], 8, Some(0x28), true);
+        // movzx eax, cl
+        check(0, &[0x0F, 0xB6, 0xC1], 3, None, false);
+    }
+
+    #[test]
+    fn immediate_after_displacement() {
+        // mov dword ptr [rip+0x10], 1, the target is relative to the end of
+        // the immediate
+        let bytes = [0xC7, 0x05, 0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+        check(0, &bytes, 10, Some(0x1A), true);
+        // mov byte ptr [rip+0x10], 1
+        check(0, &[0xC6, 0x05, 0x10, 0x00, 0x00, 0x00, 0x01], 7, Some(0x17), true);
+        // cmp qword ptr [rip+8], 0
+        check(0, &[0x48, 0x83, 0x3D, 0x08, 0x00, 0x00, 0x00, 0x00], 8, Some(0x10), true);
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(decode(&[0x48, 0x8B, 0x05, 0x10, 0x00], 0).is_none());
+        assert!(decode(&[0xE8, 0x10], 0).is_none());
+        assert!(decode(&[], 0).is_none());
+    }
+
+    /// An incremental linking thunk jumping to another one, which jumps to a
+    /// wrapper checking a global before jumping to the real function.
+    #[test]
+    fn thunk_chain() {
+        let mut mem = vec![0xCC; 0x90];
+        // 0x00: jmp 0x10
+        mem[0x00..0x05].copy_from_slice(&[0xE9, 0x0B, 0x00, 0x00, 0x00]);
+        // 0x10: jmp 0x30
+        mem[0x10..0x15].copy_from_slice(&[0xE9, 0x1B, 0x00, 0x00, 0x00]);
+        // 0x30: cmp qword ptr [rip-0x38], 0
+        mem[0x30..0x38].copy_from_slice(&[0x48, 0x83, 0x3D, 0xC8, 0xFF, 0xFF, 0xFF, 0x00]);
+        // 0x38: jz 0x3F
+        mem[0x38..0x3A].copy_from_slice(&[0x74, 0x05]);
+        // 0x3A: jmp 0x80
+        mem[0x3A..0x3F].copy_from_slice(&[0xE9, 0x41, 0x00, 0x00, 0x00]);
+        // 0x80: ret
+        mem[0x80] = 0xC3;
+
+        let mut func = 0;
+        while let Some(insn) = decode(&mem, func).filter(|x| x.is(OpcodeMap::Primary, 0xE9)) {
+            assert_eq!(insn.len, 5);
+            func = insn.target.unwrap();
+        }
+        assert_eq!(func, 0x30);
+
+        let insns = instructions(&mem, func).take(3).collect::<Vec<_>>();
+        let cmp = &insns[0];
+        assert!(cmp.rex_w() && cmp.is(OpcodeMap::Primary, 0x83) && cmp.reg() == Some(7));
+        assert_eq!((cmp.len, cmp.target, cmp.is_rip_relative()), (8, Some(0x00), true));
+        assert_eq!((insns[1].len, insns[1].target), (2, Some(0x3F)));
+        assert!(insns[2].is(OpcodeMap::Primary, 0xE9));
+        assert_eq!((insns[2].len, insns[2].target), (5, Some(0x80)));
+    }
+}
@@ -0,0 +1,7 @@
+#![feature(let_chains)]
+
+pub mod asm;
+pub mod mappings;
+pub mod pe;
+pub mod scan;
+pub mod xml;
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, path::PathBuf, process::ExitCode};
+
+use anyhow::bail;
+use clap::Parser;
+use symbol_mappings::{
+    mappings::{BinaryMappings, Version},
+    pe::PeImage,
+    scan::{Environment, MappingResult, MatchStatus, Scanner},
+};
+
+const BINARY_MAPPINGS_XML: &str = include_str!("../../BinaryMappings.xml");
+
+/// Check BinaryMappings.xml against a game executable without launching the
+/// game
+#[derive(Debug, Parser)]
+struct Args {
+    /// Path to bg3.exe, bg3_dx11.exe or a dump of either
+    exe: PathBuf,
+    /// Mappings to check instead of the ones built into the debug tool
+    #[arg(long)]
+    mappings: Option<PathBuf>,
+    /// Mappings merged over the checked ones, like BinaryMappings.override.xml
+    /// in the game
+    #[arg(long = "override")]
+    override_mappings: Option<PathBuf>,
+    /// The file is a memory dump with sections already laid out at their RVAs
+    #[arg(long)]
+    mapped: bool,
+    /// Game version used to select the `<Mappings>` blocks, e.g. 4.47.63.76.
+    /// Only the Default block is checked if omitted
+    #[arg(long)]
+    game_version: Option<Version>,
+}
+
+#[derive(Debug, Default)]
+struct OfflineEnvironment {
+    bound: HashMap<String, usize>,
+}
+
+impl Environment for OfflineEnvironment {
+    fn bind(&mut self, symbol: &str, addr: usize) -> anyhow::Result<()> {
+        match self.bound.insert(symbol.into(), addr) {
+            Some(prev) if prev != addr => {
+                bail!("'{symbol}' is also bound to {prev:#X} by another mapping")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let args = Args::parse();
+
+    let xml = match &args.mappings {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => BINARY_MAPPINGS_XML.into(),
+    };
+    let mut mappings = BinaryMappings::from_xml(&xml, args.game_version)?;
+    if let Some(path) = &args.override_mappings {
+        let overrides =
+            BinaryMappings::from_xml(&std::fs::read_to_string(path)?, args.game_version)?;
+        for name in mappings.merge(overrides.data) {
+            println!("Overridden mapping '{name}'");
+        }
+    }
+    let versions = mappings.versions.iter().map(ToString::to_string).collect::<Vec<_>>();
+    println!("Using mappings {}", versions.join(" + "));
+    let pe = PeImage::load(&std::fs::read(&args.exe)?, args.mapped)?;
+
+    let mut env = OfflineEnvironment::default();
+    let results = Scanner::new(pe.image(), &mappings, &mut env).find_all(true).run();
+
+    println!("{:<10} {:<56} {:<12} Mapping", "Status", "Symbol", "RVA");
+    let mut critical_missing = 0;
+    for result in &results {
+        let Some(mapping) = mappings.find(&result.name) else {
+            continue;
+        };
+        let status = match result.status() {
+            MatchStatus::Matched if result.unverified => "UNVERIFIED",
+            MatchStatus::Matched => "MATCHED",
+            MatchStatus::Missing => "MISSING",
+            MatchStatus::Ambiguous => "AMBIGUOUS",
+        };
+        if result.status() == MatchStatus::Missing && result.critical {
+            critical_missing += 1;
+        }
+
+        let symbols = mapping.symbols().collect::<Vec<_>>();
+
+        if symbols.is_empty() {
+            print_row(status, &format!("<{}>", result.name), result.matches.first(), result);
+        }
+        for symbol in symbols {
+            let rva = result.bindings.iter().find(|x| x.symbol == symbol).map(|x| &x.rva);
+            print_row(status, symbol, rva, result);
+        }
+        if result.status() == MatchStatus::Ambiguous {
+            let matches = result.matches.iter().map(|x| format!("{x:#010X}")).collect::<Vec<_>>();
+            println!("{:<10} {} matches: {}", "", matches.len(), matches.join(", "));
+        }
+        for err in &result.errors {
+            println!("{:<10} error: {err}", "");
+        }
+    }
+
+    let count = |status| results.iter().filter(|x| x.status() == status).count();
+    println!();
+    println!(
+        "{} matched, {} missing ({critical_missing} critical), {} ambiguous",
+        count(MatchStatus::Matched),
+        count(MatchStatus::Missing),
+        count(MatchStatus::Ambiguous),
+    );
+
+    Ok(if critical_missing == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn print_row(status: &str, symbol: &str, rva: Option<&usize>, result: &MappingResult) {
+    let rva = rva.map(|x| format!("{x:#010X}")).unwrap_or_else(|| "-".into());
+    let flags = match (result.critical, result.allow_fail) {
+        (true, _) => " (critical)",
+        (false, true) => " (allow fail)",
+        _ => "",
+    };
+    println!("{status:<10} {symbol:<56} {rva:<12} {}{flags}", result.name);
+}
@@ -0,0 +1,742 @@
+use std::{
+    fmt::{Debug, Display},
+    mem::discriminant,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail};
+
+use crate::xml;
+
+/// Game version a `<Mappings>` block applies to, e.g. `4.47.63.76`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub [u16; 4]);
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut version = [0; 4];
+        let mut parts = s.split('.');
+        for (x, part) in version.iter_mut().zip(parts.by_ref()) {
+            *x = part.trim().parse().map_err(|_| anyhow!("invalid version '{s}'"))?;
+        }
+        if parts.next().is_some() {
+            bail!("invalid version '{s}'");
+        }
+        Ok(Self(version))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [major, minor, revision, build] = self.0;
+        write!(f, "{major}.{minor}.{revision}.{build}")
+    }
+}
+
+/// A single `<Mappings>` block.
+#[derive(Clone, Debug)]
+pub struct MappingsBlock {
+    pub version: Version,
+    pub default: bool,
+    pub data: Vec<MappingOrDllImport>,
+}
+
+impl TryFrom<xml::Mappings> for MappingsBlock {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::Mappings) -> Result<Self, Self::Error> {
+        Ok(Self {
+            version: value.version.parse()?,
+            default: value.default,
+            data: value.inner.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The set of mappings selected for a game version.
+#[derive(Clone, Debug)]
+pub struct BinaryMappings {
+    /// Versions of the blocks merged into this set, in the order they were
+    /// applied
+    pub versions: Vec<Version>,
+    pub data: Vec<MappingOrDllImport>,
+}
+
+impl BinaryMappings {
+    /// Parses every `<Mappings>` block of `str` and selects the ones for
+    /// `version`, see [`BinaryMappings::select`].
+    pub fn from_xml(str: &str, version: Option<Version>) -> anyhow::Result<Self> {
+        let file = quick_xml::de::from_str::<xml::BinaryMappings>(str)?;
+        let blocks =
+            file.mappings.into_iter().map(TryInto::try_into).collect::<anyhow::Result<Vec<_>>>()?;
+        Self::select(blocks, version)
+    }
+
+    /// The `Default` block (or the first one if none is marked) is always used
+    /// as the base. Every other block up to `version` is merged over it in
+    /// document order, so a hotfix block can build on its patch's block.
+    /// Blocks older than the `Default` block are for the previous patches and
+    /// only apply to games older than it. Without a version only the
+    /// `Default` block is used.
+    pub fn select(blocks: Vec<MappingsBlock>, version: Option<Version>) -> anyhow::Result<Self> {
+        let default = blocks.iter().position(|x| x.default).unwrap_or(0);
+        let Some(base) = blocks.get(default) else {
+            bail!("no <Mappings> block found");
+        };
+
+        let mut mappings = Self { versions: vec![base.version], data: base.data.clone() };
+        let Some(version) = version else {
+            return Ok(mappings);
+        };
+        let is_newer = version >= base.version;
+        for (i, block) in blocks.iter().enumerate() {
+            if i != default
+                && block.version <= version
+                && (block.version >= base.version) == is_newer
+            {
+                mappings.merge(block.data.clone());
+                mappings.versions.push(block.version);
+            }
+        }
+
+        Ok(mappings)
+    }
+
+    /// Merges `data` over these mappings. Mappings are replaced by name and DLL
+    /// imports by symbol, everything else is appended. Returns the names of
+    /// the replaced entries.
+    pub fn merge(&mut self, data: Vec<MappingOrDllImport>) -> Vec<String> {
+        let mut overridden = Vec::new();
+        for item in data {
+            let key = item.key();
+            let same_kind = |x: &MappingOrDllImport| discriminant(x) == discriminant(&item);
+            match self.data.iter_mut().find(|x| same_kind(x) && x.key() == key) {
+                Some(x) => {
+                    overridden.push(key.to_owned());
+                    *x = item;
+                }
+                None => self.data.push(item),
+            }
+        }
+        overridden
+    }
+
+    pub fn mappings(&self) -> impl Iterator<Item = &Mapping> {
+        self.data.iter().filter_map(|x| match x {
+            MappingOrDllImport::Mapping(x) => Some(x),
+            MappingOrDllImport::DllImport(_) => None,
+        })
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Mapping> {
+        self.mappings().find(|x| x.name == name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum MappingOrDllImport {
+    DllImport(DllImport),
+    Mapping(Mapping),
+}
+
+impl MappingOrDllImport {
+    fn key(&self) -> &str {
+        match self {
+            Self::DllImport(x) => &x.symbol,
+            Self::Mapping(x) => &x.name,
+        }
+    }
+}
+
+impl TryFrom<xml::MappingOrDllImport> for MappingOrDllImport {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::MappingOrDllImport) -> Result<Self, Self::Error> {
+        Ok(match value {
+            xml::MappingOrDllImport::DllImport(x) => Self::DllImport(x.try_into()?),
+            xml::MappingOrDllImport::Mapping(x) => Self::Mapping(x.try_into()?),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DllImport {
+    pub module: String,
+    pub proc: String,
+    pub symbol: String,
+}
+
+impl TryFrom<xml::DllImport> for DllImport {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::DllImport) -> Result<Self, Self::Error> {
+        Ok(Self { module: value.module, proc: value.proc, symbol: value.symbol })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Mapping {
+    pub name: String,
+    pub critical: bool,
+    pub allow_fail: bool,
+    pub scope: MappingScope,
+    pub pattern: Pattern,
+    pub condition: Option<Condition>,
+    pub targets_or_patch: TargetsOrPatch,
+}
+
+impl Mapping {
+    /// Names of the symbols this mapping binds directly, excluding the ones
+    /// bound through NextSymbol.
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        let targets = match &self.targets_or_patch {
+            TargetsOrPatch::Targets(targets) => targets.as_slice(),
+            TargetsOrPatch::Patch(_) => &[],
+        };
+        targets.iter().filter_map(|x| match &x.value {
+            TargetValue::Symbol(x) => Some(x.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl TryFrom<xml::Mapping> for Mapping {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::Mapping) -> Result<Self, Self::Error> {
+        let mut condition = None;
+        let mut pattern = None;
+        let mut targets = Vec::new();
+        let mut patch = None;
+
+        for prop in value.props {
+            match prop {
+                xml::MappingProperty::Patch(x) => patch = Some(x),
+                xml::MappingProperty::Target(x) => targets.push(x),
+                xml::MappingProperty::Condition(x) => condition = Some(x),
+                xml::MappingProperty::Pattern(x) => pattern = Some(x),
+            }
+        }
+
+        let pattern =
+            pattern.ok_or_else(|| anyhow!("no Pattern for {}", value.name))?.try_into()?;
+
+        let targets_or_patch = if !targets.is_empty() && patch.is_none() {
+            TargetsOrPatch::Targets(
+                targets
+                    .into_iter()
+                    .map(|x| Target::from_parsed(x, &pattern))
+                    .collect::<Result<_, _>>()?,
+            )
+        } else if targets.is_empty()
+            && let Some(patch) = patch
+        {
+            TargetsOrPatch::Patch(Patch::from_parsed(patch, &pattern)?)
+        } else {
+            bail!("both Targets and Patch defined for {}", value.name);
+        };
+
+        Ok(Self {
+            name: value.name,
+            critical: value.critical,
+            allow_fail: value.allow_fail,
+            scope: value.scope.try_into()?,
+            condition: condition.map(|x| Condition::from_parsed(x, &pattern)).transpose()?,
+            pattern,
+            targets_or_patch,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MappingScope {
+    Text,
+    Custom,
+}
+
+impl TryFrom<xml::MappingScope> for MappingScope {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::MappingScope) -> Result<Self, Self::Error> {
+        match value {
+            xml::MappingScope::Text => Ok(Self::Text),
+            xml::MappingScope::Custom => Ok(Self::Custom),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    bytes: Vec<PatternByte>,
+    anchors: Vec<(String, usize)>,
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, byte) in self.bytes.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{byte:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Pattern {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut bytes = Vec::new();
+        let mut anchors = Vec::new();
+
+        let mut chars = value.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                ' ' | '\t' | '\r' | '\n' => continue,
+                '/' => {
+                    while let Some(c) = chars.next()
+                        && c != '\r'
+                        && c != '\n'
+                    {}
+                }
+                '@' => {
+                    let mut name = String::with_capacity(4);
+                    name.push('@');
+                    while let Some(c) = chars.next()
+                        && c.is_alphanumeric()
+                    {
+                        name.push(c);
+                    }
+
+                    if name.is_empty() {
+                        bail!("empty anchor name found");
+                    }
+
+                    anchors.push((name, bytes.len()));
+                }
+                // TODO: make sane
+                c1 => match (c1, chars.next(), chars.next()) {
+                    (_, None, _) => {
+                        bail!("bytes must be 2 characters long");
+                    }
+                    (_, _, Some(x)) if x.is_alphanumeric() => {
+                        bail!("bytes must be separated by whitespace");
+                    }
+                    ('?', Some('?'), _) => bytes.push(PatternByte::new(0, 0)),
+                    (_, Some(c2), _) => bytes.push(PatternByte::new(
+                        hex_to_u8(c1, c2).ok_or_else(|| anyhow!("invalid hex digit: {c1}{c2}"))?,
+                        0xFF,
+                    )),
+                },
+            }
+        }
+
+        match bytes.first() {
+            None => bail!("zero-length patterns not allowed"),
+            Some(first) if first.mask != 0xFF => {
+                bail!("first byte of pattern must be an exact match")
+            }
+            Some(_) => Ok(Pattern { bytes, anchors }),
+        }
+    }
+}
+
+impl TryFrom<&String> for Pattern {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl Pattern {
+    pub fn match_pattern(&self, mem: &[u8]) -> bool {
+        for (x, byte) in self.bytes.iter().zip(mem) {
+            if byte & x.mask != x.value {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Calls `cb` with the offset of every match in `mem` until it returns
+    /// `Some`. Returns whether a match was accepted.
+    pub fn scan_prefix_1(&self, mem: &[u8], mut cb: impl FnMut(usize) -> Option<()>) -> bool {
+        let initial = self.bytes[0].value;
+
+        for (i, _) in mem
+            .windows(self.bytes.len())
+            .enumerate()
+            .filter(|(_, win)| win[0] == initial && self.match_pattern(win))
+        {
+            if cb(i).is_some() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn scan_prefix_2(&self, mem: &[u8], mut cb: impl FnMut(usize) -> Option<()>) -> bool {
+        let initial = u16::from_ne_bytes([self.bytes[0].value, self.bytes[1].value]);
+
+        for (i, _) in mem.windows(self.bytes.len()).enumerate().filter(|(_, win)| {
+            u16::from_ne_bytes([win[0], win[1]]) == initial && self.match_pattern(win)
+        }) {
+            if cb(i).is_some() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn scan_prefix_4(&self, mem: &[u8], mut cb: impl FnMut(usize) -> Option<()>) -> bool {
+        let initial = u32::from_ne_bytes([
+            self.bytes[0].value,
+            self.bytes[1].value,
+            self.bytes[2].value,
+            self.bytes[3].value,
+        ]);
+
+        for (i, _) in mem.windows(self.bytes.len()).enumerate().filter(|(_, win)| {
+            u32::from_ne_bytes([win[0], win[1], win[2], win[3]]) == initial
+                && self.match_pattern(win)
+        }) {
+            if cb(i).is_some() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn scan(&self, mem: &[u8], cb: impl FnMut(usize) -> Option<()>) -> bool {
+        let prefix_len = self.bytes.iter().position(|b| b.mask != 0xFF).unwrap_or(0);
+
+        match prefix_len {
+            4.. => self.scan_prefix_4(mem, cb),
+            2.. => self.scan_prefix_2(mem, cb),
+            _ => self.scan_prefix_1(mem, cb),
+        }
+    }
+
+    pub fn find_anchor(&self, name: &str) -> Option<&(String, usize)> {
+        self.anchors.iter().find(|x| x.0 == name)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PatternByte {
+    value: u8,
+    mask: u8,
+}
+
+impl Debug for PatternByte {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&match self.mask {
+            0xFF => format!("{:X}{:X}", self.value >> 4, self.value & 0x0F),
+            0xF0 => format!("{:X}?", self.value >> 4),
+            0x0F => format!("?{:X}", self.value & 0x0F),
+            _ => return f.write_str("??"),
+        })
+    }
+}
+
+impl PatternByte {
+    fn new(value: u8, mask: u8) -> Self {
+        Self { value, mask }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Condition {
+    pub offset: isize,
+    pub value: ConditionValue,
+}
+
+impl Condition {
+    pub fn from_parsed(value: xml::Condition, pattern: &Pattern) -> anyhow::Result<Self> {
+        let offset = calculate_offset(&value.offset, pattern)?;
+
+        Ok(Self {
+            offset,
+            value: match value.r#type {
+                xml::ConditionType::String => ConditionValue::String(value.value),
+                xml::ConditionType::FixedString => ConditionValue::FixedString(value.value),
+                xml::ConditionType::FixedStringIndirect => {
+                    ConditionValue::FixedStringIndirect(value.value)
+                }
+            },
+        })
+    }
+
+    /// Whether checking it needs [`Environment::fixed_string`].
+    ///
+    /// [`Environment::fixed_string`]: crate::scan::Environment::fixed_string
+    pub fn reads_fixed_string(&self) -> bool {
+        matches!(
+            self.value,
+            ConditionValue::FixedString(_) | ConditionValue::FixedStringIndirect(_)
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ConditionValue {
+    String(String),
+    FixedString(String),
+    FixedStringIndirect(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum TargetsOrPatch {
+    Targets(Vec<Target>),
+    Patch(Patch),
+}
+
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub r#type: TargetType,
+    pub offset: isize,
+    pub value: TargetValue,
+}
+
+impl Target {
+    pub fn from_parsed(value: xml::Target, pattern: &Pattern) -> anyhow::Result<Self> {
+        let offset = calculate_offset(&value.offset, pattern)?;
+
+        Ok(Self {
+            r#type: value.r#type.try_into()?,
+            offset,
+            value: {
+                match (
+                    value.symbol,
+                    value.next_symbol,
+                    value.next_symbol_seek_size,
+                    value.engine_callback,
+                ) {
+                    (Some(s), None, None, None) => TargetValue::Symbol(s),
+                    (None, Some(value), Some(offset), None) => TargetValue::NextSymbol {
+                        value,
+                        offset: calculate_offset(&offset, pattern)?,
+                    },
+                    (None, None, None, Some(ec)) => TargetValue::EngineCallback(ec),
+                    (symbol, next_symbol, next_symbol_seek_size, engine_callback) => {
+                        bail!("unexpected target definition: {:#?}", xml::Target {
+                            symbol,
+                            next_symbol,
+                            next_symbol_seek_size,
+                            engine_callback,
+                            ..value
+                        })
+                    }
+                }
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum TargetType {
+    Absolute,
+    Indirect,
+}
+
+impl TryFrom<xml::TargetType> for TargetType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: xml::TargetType) -> Result<Self, Self::Error> {
+        Ok(match value {
+            xml::TargetType::Absolute => Self::Absolute,
+            xml::TargetType::Indirect => Self::Indirect,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TargetValue {
+    Symbol(String),
+    NextSymbol { value: String, offset: isize },
+    EngineCallback(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Patch {
+    pub offset: isize,
+    pub text: String,
+}
+
+impl Patch {
+    pub fn from_parsed(value: xml::Patch, pattern: &Pattern) -> anyhow::Result<Self> {
+        let offset = calculate_offset(&value.offset, pattern)?;
+
+        Ok(Self { offset, text: value.text })
+    }
+}
+
+fn hex_to_u8(c1: char, c2: char) -> Option<u8> {
+    let c1 = c1.to_digit(16)? as u8;
+    let c2 = c2.to_digit(16)? as u8;
+
+    Some((c1 << 4) + c2)
+}
+
+fn calculate_offset(offset: &str, pattern: &Pattern) -> anyhow::Result<isize> {
+    if offset.starts_with('@') {
+        if let Some((_, offset)) = pattern.find_anchor(offset) {
+            Ok(*offset as _)
+        } else {
+            bail!("unable to find offset {offset} in pattern")
+        }
+    } else {
+        let (negative, abs) = match offset.strip_prefix('-') {
+            Some(x) => (true, x),
+            None => (false, offset),
+        };
+        let abs = match abs.strip_prefix("0x").or_else(|| abs.strip_prefix("0X")) {
+            Some(hex) => isize::from_str_radix(hex, 16),
+            None => abs.parse(),
+        }
+        .map_err(|_| anyhow!("unable to parse offset {offset}"))?;
+        Ok(if negative { -abs } else { abs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREVIOUS: Version = Version([4, 46, 0, 0]);
+    const CURRENT: Version = Version([4, 47, 63, 76]);
+    const NEXT: Version = Version([4, 48, 0, 0]);
+
+    /// A block binding `Symbol` to a proc named after `version`.
+    fn block(version: Version, default: bool) -> MappingsBlock {
+        let import = DllImport {
+            module: "bg3.exe".into(),
+            proc: version.to_string(),
+            symbol: "Symbol".into(),
+        };
+        MappingsBlock { version, default, data: vec![MappingOrDllImport::DllImport(import)] }
+    }
+
+    /// The version of the block `Symbol` was bound from.
+    fn proc(mappings: &BinaryMappings) -> &str {
+        proc_of(mappings, "Symbol")
+    }
+
+    fn proc_of<'a>(mappings: &'a BinaryMappings, symbol: &str) -> &'a str {
+        mappings
+            .data
+            .iter()
+            .find_map(|x| match x {
+                MappingOrDllImport::DllImport(x) if x.symbol == symbol => Some(x.proc.as_str()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("'{symbol}' isn't bound by {:?}", mappings.data))
+    }
+
+    fn side_by_side() -> Vec<MappingsBlock> {
+        vec![block(CURRENT, true), block(PREVIOUS, false)]
+    }
+
+    /// Default, a patch block binding `Symbol` and `Other`, then a hotfix
+    /// block only rebinding `Symbol`.
+    fn stack(default: Version, patch: Version, hotfix: Version) -> Vec<MappingsBlock> {
+        let mut patch = block(patch, false);
+        let import =
+            DllImport { module: "bg3.exe".into(), proc: "patch".into(), symbol: "Other".into() };
+        patch.data.push(MappingOrDllImport::DllImport(import));
+        vec![block(default, true), patch, block(hotfix, false)]
+    }
+
+    #[test]
+    fn current_version_uses_default() {
+        let mappings = BinaryMappings::select(side_by_side(), Some(CURRENT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+        assert_eq!(proc(&mappings), CURRENT.to_string());
+    }
+
+    #[test]
+    fn previous_version_uses_its_block() {
+        let mappings = BinaryMappings::select(side_by_side(), Some(PREVIOUS)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, PREVIOUS]);
+        assert_eq!(proc(&mappings), PREVIOUS.to_string());
+    }
+
+    #[test]
+    fn unknown_version_uses_blocks_below() {
+        let mut blocks = side_by_side();
+        blocks.push(block(NEXT, false));
+        let newer = Version([4, 49, 0, 0]);
+        let mappings = BinaryMappings::select(blocks.clone(), Some(newer)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, NEXT]);
+        assert_eq!(proc(&mappings), NEXT.to_string());
+
+        let between = Version([4, 47, 70, 0]);
+        let mappings = BinaryMappings::select(blocks.clone(), Some(between)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+
+        let older = Version([4, 45, 0, 0]);
+        let mappings = BinaryMappings::select(blocks.clone(), Some(older)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+
+        let mappings = BinaryMappings::select(blocks, None).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+    }
+
+    #[test]
+    fn first_block_is_default_without_one() {
+        let blocks = vec![block(CURRENT, false), block(NEXT, false)];
+        let mappings = BinaryMappings::select(blocks.clone(), Some(CURRENT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+        let mappings = BinaryMappings::select(blocks, Some(NEXT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, NEXT]);
+        assert_eq!(proc(&mappings), NEXT.to_string());
+
+        assert!(BinaryMappings::select(Vec::new(), Some(CURRENT)).is_err());
+    }
+
+    #[test]
+    fn blocks_stack_in_order() {
+        let hotfix = Version([4, 48, 1, 0]);
+        let blocks = stack(CURRENT, NEXT, hotfix);
+        let mappings = BinaryMappings::select(blocks.clone(), Some(hotfix)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, NEXT, hotfix]);
+        assert_eq!(proc(&mappings), hotfix.to_string());
+        assert_eq!(proc_of(&mappings, "Other"), "patch");
+
+        let mappings = BinaryMappings::select(blocks, Some(NEXT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, NEXT]);
+        assert_eq!(proc(&mappings), NEXT.to_string());
+    }
+
+    #[test]
+    fn blocks_below_default_stack() {
+        let hotfix = Version([4, 46, 1, 0]);
+        let blocks = stack(CURRENT, PREVIOUS, hotfix);
+        let mappings =
+            BinaryMappings::select(blocks.clone(), Some(Version([4, 46, 5, 0]))).unwrap();
+        assert_eq!(mappings.versions, [CURRENT, PREVIOUS, hotfix]);
+        assert_eq!(proc(&mappings), hotfix.to_string());
+        assert_eq!(proc_of(&mappings, "Other"), "patch");
+
+        let mappings = BinaryMappings::select(blocks.clone(), Some(CURRENT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+        let mappings = BinaryMappings::select(blocks, Some(NEXT)).unwrap();
+        assert_eq!(mappings.versions, [CURRENT]);
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{anyhow, bail};
+
+use crate::scan::Image;
+
+/// A PE32+ executable loaded from disk and laid out by RVA, the way the Windows
+/// loader maps it.
+#[derive(Debug)]
+pub struct PeImage {
+    pub image_base: u64,
+    pub data: Vec<u8>,
+    pub text_start: usize,
+    pub text_size: usize,
+}
+
+impl PeImage {
+    /// Maps `file` section by section. If `mapped` is set, `file` is a memory
+    /// dump whose sections already sit at their RVAs and it is used as is.
+    pub fn load(file: &[u8], mapped: bool) -> anyhow::Result<Self> {
+        if file.get(..2) != Some(b"MZ") {
+            bail!("not a PE file: missing MZ signature");
+        }
+        let nt_header = read_u32(file, 0x3C)? as usize;
+        if file.get(nt_header..nt_header + 4) != Some(b"PE\0\0") {
+            bail!("not a PE file: missing PE signature");
+        }
+
+        let file_header = nt_header + 4;
+        let num_sections = read_u16(file, file_header + 2)? as usize;
+        let optional_header_size = read_u16(file, file_header + 16)? as usize;
+
+        let optional_header = file_header + 20;
+        if read_u16(file, optional_header)? != 0x20B {
+            bail!("only PE32+ images are supported");
+        }
+        let image_base = read_u64(file, optional_header + 24)?;
+        let image_size = read_u32(file, optional_header + 56)? as usize;
+        let headers_size = read_u32(file, optional_header + 60)? as usize;
+
+        let mut data = if mapped {
+            let mut data = file.to_vec();
+            data.resize(image_size.max(file.len()), 0);
+            data
+        } else {
+            let mut data = vec![0; image_size];
+            let headers_size = headers_size.min(file.len()).min(image_size);
+            data[..headers_size].copy_from_slice(&file[..headers_size]);
+            data
+        };
+
+        let mut text = None;
+        let section_table = optional_header + optional_header_size;
+        for i in 0..num_sections {
+            let section = section_table + i * 40;
+            let name = file.get(section..section + 8).ok_or_else(|| anyhow!("truncated file"))?;
+            let virtual_address = read_u32(file, section + 12)? as usize;
+            let raw_size = read_u32(file, section + 16)? as usize;
+            let raw_ptr = read_u32(file, section + 20)? as usize;
+
+            if !mapped && raw_size != 0 {
+                let raw = file
+                    .get(raw_ptr..raw_ptr + raw_size)
+                    .ok_or_else(|| anyhow!("section {i} lies outside of the file"))?;
+                let dest = data
+                    .get_mut(virtual_address..virtual_address + raw_size)
+                    .ok_or_else(|| anyhow!("section {i} lies outside of the image"))?;
+                dest.copy_from_slice(raw);
+            }
+
+            if text.is_none() && &name[..5] == b".text" {
+                text = Some((virtual_address, raw_size));
+            }
+        }
+
+        let Some((text_start, text_size)) = text else {
+            bail!("no .text section found");
+        };
+
+        Ok(Self { image_base, data, text_start, text_size })
+    }
+
+    pub fn image(&self) -> Image<'_> {
+        Image {
+            base: self.image_base as _,
+            data: &self.data,
+            text_start: self.text_start,
+            text_size: self.text_size,
+        }
+    }
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> anyhow::Result<u16> {
+    Ok(u16::from_le_bytes(read_array(buf, offset)?))
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> anyhow::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(buf, offset)?))
+}
+
+fn read_u64(buf: &[u8], offset: usize) -> anyhow::Result<u64> {
+    Ok(u64::from_le_bytes(read_array(buf, offset)?))
+}
+
+fn read_array<const N: usize>(buf: &[u8], offset: usize) -> anyhow::Result<[u8; N]> {
+    buf.get(offset..offset + N)
+        .and_then(|x| x.try_into().ok())
+        .ok_or_else(|| anyhow!("truncated file: unable to read {N} bytes at {offset:#X}"))
+}
@@ -0,0 +1,243 @@
+use std::{ffi::CStr, ops::Range};
+
+use crate::{
+    asm::resolve_instruction_ref,
+    mappings::{
+        BinaryMappings, Condition, ConditionValue, Mapping, MappingScope, TargetType, TargetValue,
+        TargetsOrPatch,
+    },
+};
+
+/// A module image laid out by RVA.
+#[derive(Debug, Clone, Copy)]
+pub struct Image<'a> {
+    /// Address the image is loaded at, or its preferred base address when it
+    /// was loaded from disk
+    pub base: usize,
+    pub data: &'a [u8],
+    pub text_start: usize,
+    pub text_size: usize,
+}
+
+impl Image<'_> {
+    pub fn text(&self) -> Range<usize> {
+        self.text_start..self.text_start + self.text_size
+    }
+}
+
+/// Where the [`Scanner`] puts the symbols it resolves.
+pub trait Environment {
+    /// Binds `symbol` to the absolute address `addr`.
+    fn bind(&mut self, symbol: &str, addr: usize) -> anyhow::Result<()>;
+
+    /// Reads the FixedString stored at the absolute address `addr`. Returns
+    /// `None` if it can't be read, in which case conditions depending on it
+    /// are treated as unverified.
+    fn fixed_string(&self, _addr: usize) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchStatus {
+    Matched,
+    Missing,
+    Ambiguous,
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub symbol: String,
+    pub rva: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MappingResult {
+    pub name: String,
+    pub critical: bool,
+    pub allow_fail: bool,
+    /// RVAs of every accepted match, the targets are bound from the first one
+    pub matches: Vec<usize>,
+    pub bindings: Vec<Binding>,
+    /// Set when a condition couldn't be checked, e.g. a FixedString that isn't
+    /// loaded yet
+    pub unverified: bool,
+    pub errors: Vec<String>,
+}
+
+impl MappingResult {
+    fn new(mapping: &Mapping) -> Self {
+        Self {
+            name: mapping.name.clone(),
+            critical: mapping.critical,
+            allow_fail: mapping.allow_fail,
+            matches: Vec::new(),
+            bindings: Vec::new(),
+            unverified: false,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn status(&self) -> MatchStatus {
+        match self.matches.len() {
+            0 => MatchStatus::Missing,
+            1 => MatchStatus::Matched,
+            _ => MatchStatus::Ambiguous,
+        }
+    }
+}
+
+/// Runs [`BinaryMappings`] against an [`Image`].
+pub struct Scanner<'a, E: Environment> {
+    image: Image<'a>,
+    mappings: &'a BinaryMappings,
+    env: &'a mut E,
+    find_all: bool,
+    results: Vec<MappingResult>,
+}
+
+impl<'a, E: Environment> Scanner<'a, E> {
+    pub fn new(image: Image<'a>, mappings: &'a BinaryMappings, env: &'a mut E) -> Self {
+        Self { image, mappings, env, find_all: false, results: Vec::new() }
+    }
+
+    /// Keep scanning after the first match so ambiguous patterns can be
+    /// reported. Targets are still only bound from the first match.
+    pub fn find_all(mut self, find_all: bool) -> Self {
+        self.find_all = find_all;
+        self
+    }
+
+    pub fn run(mut self) -> Vec<MappingResult> {
+        // FixedString conditions are read through a symbol a mapping binds,
+        // so the mappings checking one run after the others
+        let (checked, unchecked): (Vec<_>, Vec<_>) = self
+            .mappings
+            .mappings()
+            .filter(|x| matches!(x.scope, MappingScope::Text))
+            .partition(|x| x.condition.as_ref().is_some_and(Condition::reads_fixed_string));
+        for mapping in unchecked.into_iter().chain(checked) {
+            self.map(mapping, self.image.text());
+        }
+
+        for mapping in self.mappings.mappings() {
+            if !self.results.iter().any(|x| x.name == mapping.name) {
+                self.results.push(MappingResult::new(mapping));
+            }
+        }
+
+        self.results
+    }
+
+    fn map(&mut self, mapping: &'a Mapping, range: Range<usize>) {
+        let index = self.results.len();
+        self.results.push(MappingResult::new(mapping));
+
+        let data = self.image.data;
+        let end = range.end.min(data.len());
+        let range = range.start.min(end)..end;
+        let mut matches = Vec::new();
+        let mut unverified = false;
+
+        mapping.pattern.scan(&data[range.clone()], |offset| {
+            let addr = range.start + offset;
+            if let Some(cond) = &mapping.condition {
+                match self.evaluate_condition(cond, addr) {
+                    Some(false) => return None,
+                    Some(true) => (),
+                    None => unverified = true,
+                }
+            }
+            matches.push(addr);
+            (!self.find_all).then_some(())
+        });
+
+        if let Some(&addr) = matches.first() {
+            self.bind_targets(index, mapping, addr);
+        }
+
+        let result = &mut self.results[index];
+        result.matches = matches;
+        result.unverified = unverified;
+    }
+
+    fn bind_targets(&mut self, index: usize, mapping: &'a Mapping, addr: usize) {
+        let TargetsOrPatch::Targets(targets) = &mapping.targets_or_patch else {
+            return;
+        };
+
+        for target in targets {
+            let Some(insn) = addr.checked_add_signed(target.offset) else {
+                self.results[index]
+                    .errors
+                    .push(format!("target offset {} out of range", target.offset));
+                continue;
+            };
+            let target_addr = match target.r#type {
+                TargetType::Absolute => insn,
+                TargetType::Indirect => match resolve_instruction_ref(self.image.data, insn) {
+                    Some(x) => x,
+                    None => {
+                        self.results[index]
+                            .errors
+                            .push(format!("unsupported instruction at RVA {insn:#X}"));
+                        continue;
+                    }
+                },
+            };
+
+            match &target.value {
+                TargetValue::Symbol(symbol) => {
+                    match self.env.bind(symbol, self.image.base + target_addr) {
+                        Ok(()) => self.results[index]
+                            .bindings
+                            .push(Binding { symbol: symbol.clone(), rva: target_addr }),
+                        Err(x) => self.results[index].errors.push(x.to_string()),
+                    }
+                }
+                TargetValue::NextSymbol { value, offset } => {
+                    let end =
+                        usize::try_from(*offset).ok().and_then(|x| target_addr.checked_add(x));
+                    match (self.mappings.find(value), end) {
+                        (Some(next), Some(end)) => self.map(next, target_addr..end),
+                        (Some(_), None) => self.results[index]
+                            .errors
+                            .push(format!("NextSymbol seek size {offset} out of range")),
+                        (None, _) => {
+                            self.results[index].errors.push(format!("unknown mapping '{value}'"))
+                        }
+                    }
+                }
+                TargetValue::EngineCallback(_) => (),
+            }
+        }
+    }
+
+    /// Returns `None` if the condition can't be evaluated.
+    fn evaluate_condition(&self, cond: &Condition, addr: usize) -> Option<bool> {
+        let data = self.image.data;
+        let Some(target) = addr
+            .checked_add_signed(cond.offset)
+            .and_then(|insn| resolve_instruction_ref(data, insn))
+        else {
+            return Some(false);
+        };
+
+        match &cond.value {
+            ConditionValue::String(str) => Some(
+                data.get(target..)
+                    .and_then(|x| CStr::from_bytes_until_nul(x).ok())
+                    .is_some_and(|x| x.to_bytes() == str.as_bytes()),
+            ),
+            ConditionValue::FixedString(_) if target >= data.len() => Some(false),
+            ConditionValue::FixedString(str) => {
+                Some(self.env.fixed_string(self.image.base + target)? == *str)
+            }
+            ConditionValue::FixedStringIndirect(str) => {
+                let ptr = data.get(target..target + 8)?;
+                let ptr = u64::from_le_bytes(ptr.try_into().ok()?) as usize;
+                Some(self.env.fixed_string(ptr)? == *str)
+            }
+        }
+    }
+}
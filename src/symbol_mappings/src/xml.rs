@@ -0,0 +1,117 @@
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct BinaryMappings {
+    #[serde(rename = "Mappings")]
+    pub mappings: Vec<Mappings>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Mappings {
+    #[serde(rename = "@Version")]
+    pub version: String,
+    #[serde(rename = "@Default", default)]
+    pub default: bool,
+    #[serde(rename = "$value")]
+    pub inner: Vec<MappingOrDllImport>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub enum MappingOrDllImport {
+    DllImport(DllImport),
+    Mapping(Mapping),
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct DllImport {
+    #[serde(rename = "@Module")]
+    pub module: String,
+    #[serde(rename = "@Proc")]
+    pub proc: String,
+    #[serde(rename = "@Symbol")]
+    pub symbol: String,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Mapping {
+    #[serde(rename = "@Name")]
+    pub name: String,
+    #[serde(rename = "@Critical", default)]
+    pub critical: bool,
+    #[serde(rename = "@AllowFail", default)]
+    pub allow_fail: bool,
+    #[serde(rename = "@Scope", default)]
+    pub scope: MappingScope,
+    #[serde(rename = "$value")]
+    pub props: Vec<MappingProperty>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub enum MappingProperty {
+    Patch(Patch),
+    Target(Target),
+    Condition(Condition),
+    #[serde(rename = "$text")]
+    Pattern(String),
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, Default)]
+pub enum MappingScope {
+    #[default]
+    Text,
+    Custom,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Condition {
+    #[serde(rename = "@Type")]
+    pub r#type: ConditionType,
+    #[serde(rename = "@Offset")]
+    pub offset: String,
+    #[serde(rename = "@Value")]
+    pub value: String,
+}
+
+#[derive(Clone, Copy, Deserialize, Debug)]
+pub enum ConditionType {
+    String,
+    FixedString,
+    FixedStringIndirect,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Target {
+    #[serde(rename = "@Type")]
+    pub r#type: TargetType,
+    #[serde(rename = "@Offset")]
+    pub offset: String,
+    #[serde(rename = "@Symbol")]
+    pub symbol: Option<String>,
+    #[serde(rename = "@NextSymbol")]
+    pub next_symbol: Option<String>,
+    #[serde(rename = "@NextSymbolSeekSize")]
+    pub next_symbol_seek_size: Option<String>,
+    #[serde(rename = "@EngineCallback")]
+    pub engine_callback: Option<String>,
+}
+
+#[derive(Clone, Copy, Deserialize, Debug)]
+pub enum TargetType {
+    Absolute,
+    Indirect,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Patch {
+    #[serde(rename = "@Type")]
+    pub r#type: PatchType,
+    #[serde(rename = "@Offset")]
+    pub offset: String,
+    #[serde(rename = "$text")]
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Deserialize, Debug)]
+pub enum PatchType {
+    Absolute,
+}
@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use symbol_mappings::mappings::Version;
 use windows::{
     core::{w, PCWSTR},
     Win32::{
@@ -30,6 +31,12 @@ impl Display for GameVersionInfo {
     }
 }
 
+impl From<GameVersionInfo> for Version {
+    fn from(value: GameVersionInfo) -> Self {
+        Self([value.major, value.minor, value.revision, value.build])
+    }
+}
+
 impl GameVersionInfo {
     #[inline]
     pub(crate) fn is_supported(&self) -> bool {
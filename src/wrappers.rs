@@ -1 +1,5 @@
+#[cfg(not(feature = "osi-no-debugger"))]
+pub(crate) mod debugger;
 pub(crate) mod osiris;
+pub(crate) mod script;
+pub(crate) mod trace;
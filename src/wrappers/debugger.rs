@@ -0,0 +1,260 @@
+use std::{
+    cell::{Cell, RefCell},
+    ffi::CString,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Condvar,
+    },
+};
+
+use crate::{
+    err,
+    game_definitions::{GamePtr, OsiArgumentDesc, OsiArgumentValue, ValueType},
+    globals::Globals,
+    wrappers::{osiris::Value, trace::TraceKind},
+};
+
+/// Signalled by the menu when the paused story thread may continue.
+static RESUME: Condvar = Condvar::new();
+/// Whether there are enabled breakpoints or a pending step, read by the hooks
+/// without locking so an unused debugger costs nothing.
+static ARMED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Osiris functions being executed on this thread, innermost last.
+    static STACK: RefCell<Vec<StackFrame>> = const { RefCell::new(Vec::new()) };
+    /// Set on the thread drawing the menu, which must never be paused.
+    static IS_UI_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug)]
+struct StackFrame {
+    kind: TraceKind,
+    name: String,
+    params: GamePtr<OsiArgumentDesc<'static>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Breakpoint {
+    /// Function name without the arity, case insensitive
+    pub name: String,
+    /// Comma separated argument values, see [`Breakpoint::matches`]
+    pub condition: String,
+    pub enabled: bool,
+    pub hits: usize,
+}
+
+impl Breakpoint {
+    /// Checked before the arguments are read, which is only needed for the
+    /// condition.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.enabled && self.name.trim().eq_ignore_ascii_case(name)
+    }
+
+    /// Each condition term is compared with the argument at the same
+    /// position. Empty terms and `_` match anything, a leading `!` negates
+    /// the comparison.
+    pub fn matches(&self, name: &str, args: &[Value]) -> bool {
+        if !self.matches_name(name) {
+            return false;
+        }
+        if self.condition.trim().is_empty() {
+            return true;
+        }
+
+        self.condition.split(',').map(str::trim).enumerate().all(|(i, term)| {
+            if term.is_empty() || term == "_" {
+                return true;
+            }
+            let (negated, term) = match term.strip_prefix('!') {
+                Some(x) => (true, x.trim()),
+                None => (false, term),
+            };
+            args.get(i).is_some_and(|x| x.to_string().eq_ignore_ascii_case(term) != negated)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PausedArg {
+    pub r#type: ValueType,
+    pub original: String,
+    /// Edited by the menu, applied when resuming
+    pub text: String,
+}
+
+/// The function the story thread is blocked in.
+#[derive(Debug, Clone)]
+pub(crate) struct PausedCall {
+    pub kind: TraceKind,
+    pub name: String,
+    /// `(kind, name, arguments)` of every function being executed, innermost
+    /// last. Osiris doesn't export its rule nodes, so only the events, calls
+    /// and queries that went through the hooks while the debugger was armed
+    /// are listed.
+    pub stack: Vec<(TraceKind, String, String)>,
+    pub args: Vec<PausedArg>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Debugger {
+    pub breakpoints: Vec<Breakpoint>,
+    /// Pause on the next function regardless of the breakpoints
+    pub step: bool,
+    pub paused: Option<PausedCall>,
+    resumed: bool,
+}
+
+impl Debugger {
+    pub const fn new() -> Self {
+        Self { breakpoints: Vec::new(), step: false, paused: None, resumed: false }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some() && !self.resumed
+    }
+
+    /// Lets the paused function run with the edited arguments, `step` pauses
+    /// again on the next one.
+    pub fn resume(&mut self, step: bool) {
+        self.step = step;
+        self.resumed = true;
+        self.update_armed();
+        RESUME.notify_all();
+    }
+
+    /// Called after changing `breakpoints` or `step`.
+    pub fn update_armed(&self) {
+        let armed = self.step || self.breakpoints.iter().any(|x| x.enabled);
+        ARMED.store(armed, Ordering::Relaxed);
+    }
+}
+
+/// Keeps the function on the stack, and the values of modified arguments
+/// alive, until it returned.
+#[derive(Debug, Default)]
+pub(crate) struct FrameGuard {
+    /// Unset when the debugger wasn't armed
+    pushed: bool,
+    _values: Vec<Value>,
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        if self.pushed {
+            STACK.with_borrow_mut(|x| x.pop());
+        }
+    }
+}
+
+pub(crate) fn mark_ui_thread() {
+    IS_UI_THREAD.set(true);
+}
+
+/// Called by the hooks before the original function, blocks while the
+/// debugger is paused on it.
+pub(crate) fn enter(kind: TraceKind, name: &str, params: GamePtr<OsiArgumentDesc>) -> FrameGuard {
+    if !is_armed() {
+        return FrameGuard::default();
+    }
+    STACK.with_borrow_mut(|x| {
+        x.push(StackFrame { kind, name: name.into(), params: GamePtr::new(params.ptr as _) })
+    });
+    let mut frame = FrameGuard { pushed: true, _values: Vec::new() };
+    if IS_UI_THREAD.get() {
+        return frame;
+    }
+
+    let mut debugger = Globals::debugger();
+    if !debugger.step && !debugger.breakpoints.iter().any(|x| x.matches_name(name)) {
+        return frame;
+    }
+
+    // Query out parameters aren't filled in yet, the unset ones read as None
+    let args = params.as_opt().map(|x| x.iter().collect::<Vec<_>>()).unwrap_or_default();
+    let values = args.iter().map(Value::from_ffi).collect::<Vec<_>>();
+    let mut hit = debugger.step;
+    for bp in debugger.breakpoints.iter_mut().filter(|x| x.matches(name, &values)) {
+        bp.hits += 1;
+        hit = true;
+    }
+    if !hit {
+        return frame;
+    }
+
+    debugger.step = false;
+    debugger.update_armed();
+    debugger.resumed = false;
+    debugger.paused = Some(PausedCall {
+        kind,
+        name: name.into(),
+        stack: STACK.with_borrow(|x| x.iter().map(describe).collect()),
+        args: args
+            .iter()
+            .zip(&values)
+            .map(|(arg, value)| PausedArg {
+                r#type: arg.type_id,
+                original: value.to_string(),
+                text: value.to_string(),
+            })
+            .collect(),
+    });
+    while !debugger.resumed {
+        debugger = RESUME.wait(debugger).unwrap();
+    }
+    let Some(paused) = debugger.paused.take() else {
+        return frame;
+    };
+    drop(debugger);
+
+    let mut modified = Vec::new();
+    for (i, arg) in paused.args.iter().enumerate().filter(|(_, x)| x.text != x.original) {
+        let Some(value) = parse_value(&arg.text, arg.r#type) else {
+            err!("{name}: invalid {:?} value for argument {i}: '{}'", arg.r#type, arg.text);
+            continue;
+        };
+        if let Some(ffi) = value.to_ffi(arg.r#type) {
+            unsafe { set_arg(params, i, ffi) };
+            modified.push(value);
+        }
+    }
+
+    frame._values = modified;
+    frame
+}
+
+pub(crate) fn is_armed() -> bool {
+    ARMED.load(Ordering::Relaxed)
+}
+
+fn describe(frame: &StackFrame) -> (TraceKind, String, String) {
+    let args = frame
+        .params
+        .as_opt()
+        .map(|x| x.iter().map(|x| Value::from_ffi(&x).to_string()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+    (frame.kind, frame.name.clone(), args)
+}
+
+fn parse_value(text: &str, r#type: ValueType) -> Option<Value> {
+    match r#type {
+        ValueType::None | ValueType::Undefined => Some(Value::None),
+        ValueType::Integer | ValueType::Integer64 => text.trim().parse().ok().map(Value::Int),
+        ValueType::Real => text.trim().parse().ok().map(Value::Float),
+        _ => CString::new(text).ok().map(Value::String),
+    }
+}
+
+/// Overwrites the `i`th argument of the game's argument list in place.
+unsafe fn set_arg(params: GamePtr<OsiArgumentDesc>, i: usize, value: OsiArgumentValue) {
+    let mut current = params.ptr;
+    for _ in 0..i {
+        if current.is_null() {
+            return;
+        }
+        current = (*current).next_param.map_or(std::ptr::null_mut(), |x| x as *const _ as *mut _);
+    }
+    if let Some(arg) = current.as_mut() {
+        arg.value = value;
+    }
+}
@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::bail;
+use itertools::Itertools;
 
 use crate::{
     game_definitions::{OsiArgumentDesc, OsiArgumentValue, OsiString, ValueType},
@@ -33,20 +34,9 @@ pub(crate) struct FunctionCall {
     pub args: Vec<Value>,
 }
 
-impl syn::parse::Parse for FunctionCall {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let name = input.parse::<syn::Ident>()?;
-        let content;
-        syn::parenthesized!(content in input);
-        let args = content.parse_terminated(Value::parse, syn::Token![,])?.into_iter().collect();
-
-        Ok(Self { ident: name.to_string(), args })
-    }
-}
-
 impl FunctionCall {
     pub fn call(&self) -> anyhow::Result<Option<Value>> {
-        Function::new(&self.ident, self.args.len())?(self.args.iter().cloned())
+        Function::resolve(&self.ident, &self.args)?(self.args.iter().cloned())
     }
 }
 
@@ -77,12 +67,49 @@ impl<T: IntoIterator<Item = impl Into<Value>>> Fn<(T,)> for Function {
 }
 
 impl Function {
-    pub fn new(name: impl AsRef<str>, n_args: usize) -> anyhow::Result<Self> {
+    /// Picks the call or query named `name` whose parameters accept `args`,
+    /// listing every candidate signature if none does.
+    pub fn resolve(name: impl AsRef<str>, args: &[Value]) -> anyhow::Result<Self> {
         let name = name.as_ref();
-        if let Ok(f) = Call::new(name, n_args) {
-            return Ok(Self::Call(f));
+        let candidates = [
+            Call::new(name, args.len()).map(Self::Call),
+            Query::new(name, args.len()).map(Self::Query),
+        ];
+        Self::select(name, candidates.into_iter().flatten(), args)
+    }
+
+    fn select(
+        name: &str,
+        candidates: impl IntoIterator<Item = Self>,
+        args: &[Value],
+    ) -> anyhow::Result<Self> {
+        let mut signatures = Vec::new();
+        for candidate in candidates {
+            if candidate.accepts(args) {
+                return Ok(candidate);
+            }
+            signatures.push(candidate.signature());
+        }
+        if signatures.is_empty() {
+            bail!("unable to find a call or query '{name}' with {} arguments", args.len());
+        }
+
+        let provided = args.iter().map(|x| x.type_str()).join(", ");
+        bail!("{name}: no overload accepts ({provided}), expected {}", signatures.join(" or "))
+    }
+
+    pub fn accepts(&self, args: &[Value]) -> bool {
+        match self {
+            Function::Call(x) => x.accepts(args),
+            Function::Query(x) => x.accepts(args),
+        }
+    }
+
+    pub fn signature(&self) -> String {
+        match self {
+            Function::Call(x) => x.signature(),
+            Function::Query(x) => x.signature(),
         }
-        Ok(Self::Query(Query::new(name, n_args)?))
     }
 
     pub fn call_fn(
@@ -124,6 +151,16 @@ impl Call {
         Ok(Self { name: name.into(), handle: f.handle(), args })
     }
 
+    pub fn accepts(&self, args: &[Value]) -> bool {
+        self.args.len() == args.len()
+            && args.iter().zip(&self.args).all(|(x, r#type)| x.to_ffi(*r#type).is_some())
+    }
+
+    /// e.g. `ApplyStatus(GuidString, String, Real, Integer, GuidString)`
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.args.iter().map(|x| format!("{x:?}")).join(", "))
+    }
+
     pub fn call(&self, args: impl IntoIterator<Item = impl Into<Value>>) -> anyhow::Result<()> {
         let args = args.into_iter().map(Into::into).collect::<Vec<Value>>();
         if self.args.len() != args.len() {
@@ -142,10 +179,11 @@ impl Call {
             } else {
                 bail!(
                     "call {}: incorrect function parameter type {i}: expected {expected:?}, got \
-                     {} ({})",
+                     {} ({}), the signature is {}",
                     self.name,
                     provided.type_str(),
                     provided,
+                    self.signature(),
                 );
             }
         }
@@ -192,7 +230,39 @@ impl Query {
         Ok(Self { name: name.into(), handle: f.handle(), args })
     }
 
+    pub fn accepts(&self, args: &[Value]) -> bool {
+        let inputs = self.args.iter().filter(|x| matches!(x, Arg::In(_))).collect::<Vec<_>>();
+        inputs.len() == args.len()
+            && args.iter().zip(inputs).all(|(x, arg)| x.to_ffi(arg.r#type()).is_some())
+    }
+
+    /// e.g. `HasActiveStatus(GuidString, String, out Integer)`
+    pub fn signature(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|x| match x {
+                Arg::In(x) => format!("{x:?}"),
+                Arg::Out(x) => format!("out {x:?}"),
+            })
+            .join(", ");
+        format!("{}({})", self.name, args)
+    }
+
     pub fn call(&self, args: impl IntoIterator<Item = impl Into<Value>>) -> anyhow::Result<Value> {
+        let args = args.into_iter().map(Into::into).collect::<Vec<Value>>();
+        match self.try_call(args.iter().cloned())? {
+            Some(x) => Ok(x),
+            None => bail!("query {} failed with args [{}]", self.name, args.iter().join(", ")),
+        }
+    }
+
+    /// Like [`Query::call`], but a query that doesn't hold returns `None`
+    /// instead of an error.
+    pub fn try_call(
+        &self,
+        args: impl IntoIterator<Item = impl Into<Value>>,
+    ) -> anyhow::Result<Option<Value>> {
         let mut args = args.into_iter().map(Into::into).collect::<Vec<Value>>();
         if self.args.len() - 1 != args.len() {
             bail!(
@@ -214,20 +284,22 @@ impl Query {
                 new_args.push(arg);
             } else {
                 bail!(
-                    "query {}: incorrect function parameter type {i}: expected {:?}, got {} ({})",
+                    "query {}: incorrect function parameter type {i}: expected {:?}, got {} ({}), \
+                     the signature is {}",
                     self.name,
                     expected.r#type(),
                     provided.type_str(),
                     provided,
+                    self.signature(),
                 );
             }
         }
 
         OsiArgumentDesc::from_values(new_args, |args| {
             if !osiris::Query(self.handle, args.into()) {
-                bail!("query {} failed with args {}", self.name, args);
+                return Ok(None);
             }
-            Ok(Value::from_ffi(&args.iter().nth(ret_i).unwrap()))
+            Ok(Some(Value::from_ffi(&args.iter().nth(ret_i).unwrap())))
         })
     }
 }
@@ -255,26 +327,6 @@ pub(crate) enum Value {
     String(std::ffi::CString),
 }
 
-impl syn::parse::Parse for Value {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        Ok(if let Ok(lit) = input.parse::<syn::Lit>() {
-            match lit {
-                syn::Lit::Int(int) => Self::Int(int.base10_parse()?),
-                syn::Lit::Float(float) => Self::Float(float.base10_parse()?),
-                syn::Lit::Str(str) => Self::String(CString::new(str.value()).unwrap()),
-                x => return Err(input.error(format!("unexpected literal '{x:?}'"))),
-            }
-        } else {
-            let ident = input.parse::<syn::Ident>()?;
-
-            match ident.to_string().as_str() {
-                "None" => Self::None,
-                x => return Err(input.error(format!("unknown Osiris argument type '{x}'"))),
-            }
-        })
-    }
-}
-
 impl From<i32> for Value {
     fn from(value: i32) -> Self {
         Self::Int(value as _)
@@ -311,7 +363,8 @@ impl Display for Value {
             Value::None => f.write_str("None"),
             Value::Int(x) => Display::fmt(x, f),
             Value::Float(x) => Display::fmt(x, f),
-            Value::String(x) => Display::fmt(x.to_str().unwrap(), f),
+            // Game strings aren't always valid UTF-8
+            Value::String(x) => Display::fmt(&x.to_string_lossy(), f),
         }
     }
 }
@@ -392,6 +445,8 @@ impl Value {
                 ValueType::Integer64 => Self::Int(value.value.int64),
                 ValueType::Real => Self::Float(value.value.float),
                 ValueType::Undefined => Self::None,
+                // Out parameters of a query that failed are never filled in
+                _ if value.value.string.is_null() => Self::None,
                 _ => Self::String(std::ffi::CStr::from_ptr(value.value.string).into()),
             }
         }
@@ -418,3 +473,103 @@ fn function_name_hash(str: &[u8]) -> u32 {
 
     hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_ffi_unfilled_out_param() {
+        for r#type in [ValueType::String, ValueType::GuidString, ValueType::Character] {
+            let out = Value::None.to_ffi(r#type).unwrap();
+            assert!(matches!(Value::from_ffi(&out), Value::None), "{type:?}");
+        }
+    }
+
+    #[test]
+    fn from_ffi_string() {
+        let str = c"S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c";
+        let value = OsiArgumentValue::string(str.as_ptr(), ValueType::GuidString);
+        assert!(matches!(Value::from_ffi(&value), Value::String(x) if x.as_c_str() == str));
+    }
+
+    fn apply_status() -> Call {
+        let args = vec![ValueType::GuidString, ValueType::String, ValueType::Real];
+        Call { name: "ApplyStatus".into(), handle: 0, args }
+    }
+
+    fn has_active_status() -> Query {
+        let args = vec![
+            Arg::In(ValueType::GuidString),
+            Arg::In(ValueType::String),
+            Arg::Out(ValueType::Integer),
+        ];
+        Query { name: "HasActiveStatus".into(), handle: 0, args }
+    }
+
+    #[test]
+    fn accepts_by_type() {
+        let call = apply_status();
+        assert!(call.accepts(&["S_Player_Karlach".into(), "BURNING".into(), (-5).into()]));
+        assert!(call.accepts(&[Value::None, "BURNING".into(), 6.0.into()]));
+        assert!(!call.accepts(&[1.into(), "BURNING".into(), 6.0.into()]));
+        assert!(!call.accepts(&["S_Player_Karlach".into(), "BURNING".into()]));
+
+        let query = has_active_status();
+        assert!(query.accepts(&["S_Player_Karlach".into(), "BURNING".into()]));
+        assert!(!query.accepts(&["S_Player_Karlach".into(), 1.into()]));
+    }
+
+    #[test]
+    fn signatures() {
+        assert_eq!(apply_status().signature(), "ApplyStatus(GuidString, String, Real)");
+        assert_eq!(
+            has_active_status().signature(),
+            "HasActiveStatus(GuidString, String, out Integer)"
+        );
+    }
+
+    #[test]
+    fn select_overload() {
+        let args = ["S_Player_Karlach".into(), "BURNING".into()];
+        let candidates = [Function::Call(apply_status()), Function::Query(has_active_status())];
+        let f = Function::select("Status", candidates, &args).unwrap();
+        assert!(matches!(f, Function::Query(_)));
+
+        let args = [1.into(), "BURNING".into(), 6.0.into()];
+        let candidates = [Function::Call(apply_status()), Function::Call(apply_status())];
+        let err = Function::select("ApplyStatus", candidates, &args).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ApplyStatus: no overload accepts (Integer, String, Real), expected \
+             ApplyStatus(GuidString, String, Real) or ApplyStatus(GuidString, String, Real)"
+        );
+
+        let err = Function::select("Missing", [], &args).unwrap_err();
+        assert_eq!(err.to_string(), "unable to find a call or query 'Missing' with 3 arguments");
+    }
+
+    #[test]
+    fn call_type_mismatch_lists_signature() {
+        let err =
+            apply_status().call([Value::from("S_Player_Karlach"), "BURNING".into(), "6".into()]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "call ApplyStatus: incorrect function parameter type 2: expected Real, got String \
+             (6), the signature is ApplyStatus(GuidString, String, Real)"
+        );
+
+        let err = has_active_status().try_call([Value::from(1), "BURNING".into()]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "query HasActiveStatus: incorrect function parameter type 0: expected GuidString, got \
+             Integer (1), the signature is HasActiveStatus(GuidString, String, out Integer)"
+        );
+    }
+
+    #[test]
+    fn display_invalid_utf8() {
+        let value = Value::String(CString::new(b"Karlach \xFF".to_vec()).unwrap());
+        assert_eq!(value.to_string(), "Karlach \u{FFFD}");
+    }
+}
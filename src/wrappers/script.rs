@@ -0,0 +1,773 @@
+use std::{
+    collections::BTreeMap,
+    ffi::CString,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail};
+
+use crate::{
+    game_definitions::FunctionType,
+    globals::Globals,
+    osi_fn,
+    wrappers::osiris::{Function, FunctionCall, Value},
+};
+
+/// Extension added to `include` paths that don't have one.
+pub(crate) const SCRIPT_EXTENSION: &str = "osiscript";
+/// Limit on nested macro calls and includes, in case they recurse.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Names, numbers and bare strings such as GUIDs
+    Word(String),
+    Variable(String),
+    Str(String),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(Value),
+    Variable(String),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Iterable {
+    /// Every fact of an Osiris database, one loop variable per column
+    Database(String),
+    List(Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Expr(Expr),
+    Let(String, Expr),
+    For(Vec<String>, Iterable, Vec<Stmt>),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Macro(String, Vec<String>, Vec<Stmt>),
+    Include(String),
+}
+
+#[derive(Debug, Clone)]
+struct Macro {
+    params: Vec<String>,
+    body: Vec<Stmt>,
+}
+
+/// Variables and macros, kept between the scripts run in the console.
+#[derive(Debug, Default)]
+pub(crate) struct ScriptEnv {
+    pub variables: BTreeMap<String, Value>,
+    macros: BTreeMap<String, Macro>,
+    /// Relative `include` paths are resolved against this
+    pub dir: Option<PathBuf>,
+    depth: usize,
+}
+
+impl ScriptEnv {
+    /// Runs `text`, appending the results of its top level expressions to
+    /// `out`.
+    pub fn run(&mut self, text: &str, out: &mut String) -> anyhow::Result<()> {
+        let script = Parser::new(tokenize(text)?).script()?;
+        self.depth = 0;
+        self.exec_block(&script, Some(out))?;
+        Ok(())
+    }
+
+    /// Name and `name($param, ...)` signature of every defined macro.
+    pub fn macros(&self) -> impl Iterator<Item = (&str, String)> {
+        self.macros.iter().map(|(name, m)| {
+            let params = m.params.iter().map(|x| format!("${x}")).collect::<Vec<_>>();
+            (name.as_str(), format!("{name}({})", params.join(", ")))
+        })
+    }
+
+    /// Returns the value of the last expression, which is what macros return.
+    fn exec_block(
+        &mut self,
+        stmts: &[Stmt],
+        mut out: Option<&mut String>,
+    ) -> anyhow::Result<Option<Value>> {
+        let mut last = None;
+        for stmt in stmts {
+            last = self.exec(stmt, out.as_deref_mut())?;
+        }
+        Ok(last)
+    }
+
+    fn exec(&mut self, stmt: &Stmt, out: Option<&mut String>) -> anyhow::Result<Option<Value>> {
+        match stmt {
+            Stmt::Expr(expr) => {
+                let value = self.eval(expr)?;
+                if let (Some(out), Some(value)) = (out, &value) {
+                    out.push_str(&value.to_string());
+                    out.push('\n');
+                }
+                return Ok(value);
+            }
+            Stmt::Let(name, expr) => {
+                let value = self.eval_arg(expr)?;
+                self.variables.insert(name.clone(), value);
+            }
+            Stmt::For(vars, iterable, body) => {
+                let rows = match iterable {
+                    Iterable::Database(name) => database_rows(name, vars.len())?,
+                    Iterable::List(exprs) => {
+                        if vars.len() != 1 {
+                            bail!("lists are iterated with a single variable");
+                        }
+                        exprs
+                            .iter()
+                            .map(|x| self.eval_arg(x).map(|x| vec![x]))
+                            .collect::<anyhow::Result<_>>()?
+                    }
+                };
+                // Loop variables shadow variables of the same name until it
+                // ends
+                let saved =
+                    vars.iter().map(|x| (x.clone(), self.variables.remove(x))).collect::<Vec<_>>();
+                let res = rows.into_iter().try_for_each(|row| {
+                    self.variables.extend(vars.iter().cloned().zip(row));
+                    self.exec_block(body, None).map(drop)
+                });
+                for (name, value) in saved {
+                    match value {
+                        Some(value) => self.variables.insert(name, value),
+                        None => self.variables.remove(&name),
+                    };
+                }
+                res?;
+            }
+            Stmt::If(condition, then, otherwise) => {
+                if self.condition(condition)? {
+                    self.exec_block(then, None)?;
+                } else {
+                    self.exec_block(otherwise, None)?;
+                }
+            }
+            Stmt::Macro(name, params, body) => {
+                self.macros
+                    .insert(name.clone(), Macro { params: params.clone(), body: body.clone() });
+            }
+            Stmt::Include(path) => {
+                let mut path = PathBuf::from(path);
+                if path.extension().is_none() {
+                    path.set_extension(SCRIPT_EXTENSION);
+                }
+                if let Some(dir) = &self.dir
+                    && path.is_relative()
+                {
+                    path = dir.join(path);
+                }
+                self.include(&path)?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn include(&mut self, path: &Path) -> anyhow::Result<()> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+        let script = Parser::new(tokenize(&text)?)
+            .script()
+            .map_err(|e| anyhow!("{}: {e}", path.display()))?;
+        self.nested(|env| env.exec_block(&script, None))?;
+        Ok(())
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        if self.depth >= MAX_DEPTH {
+            bail!("too many nested macros or includes");
+        }
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn eval(&mut self, expr: &Expr) -> anyhow::Result<Option<Value>> {
+        match expr {
+            Expr::Value(x) => Ok(Some(x.clone())),
+            Expr::Variable(name) => self.variable(name).map(Some),
+            Expr::Call(name, args) => {
+                let args = args.iter().map(|x| self.eval_arg(x)).collect::<anyhow::Result<_>>()?;
+                if let Some(m) = self.macros.get(name).cloned() {
+                    return self.call_macro(name, &m, args);
+                }
+                let value = FunctionCall { ident: name.clone(), args }.call()?;
+                if let Some(value) = &value {
+                    self.variables.insert("last".into(), value.clone());
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    fn eval_arg(&mut self, expr: &Expr) -> anyhow::Result<Value> {
+        self.eval(expr)?.ok_or_else(|| match expr {
+            Expr::Call(name, _) => anyhow!("{name} doesn't return a value"),
+            _ => anyhow!("missing value"),
+        })
+    }
+
+    /// Macro parameters shadow variables of the same name until it returns.
+    fn call_macro(
+        &mut self,
+        name: &str,
+        m: &Macro,
+        args: Vec<Value>,
+    ) -> anyhow::Result<Option<Value>> {
+        if m.params.len() != args.len() {
+            bail!("macro {name}: expected {} arguments, got {}", m.params.len(), args.len());
+        }
+        let saved = self.variables.clone();
+        self.variables.extend(m.params.iter().cloned().zip(args));
+        let res = self.nested(|env| env.exec_block(&m.body, None));
+        self.variables = saved;
+        res
+    }
+
+    /// Queries that don't hold are false, as are `0`, `""` and `None`.
+    fn condition(&mut self, expr: &Expr) -> anyhow::Result<bool> {
+        if let Expr::Call(name, args) = expr
+            && !self.macros.contains_key(name)
+        {
+            let args = args.iter().map(|x| self.eval_arg(x)).collect::<anyhow::Result<Vec<_>>>()?;
+            return match Function::resolve(name, &args)? {
+                Function::Query(query) => Ok(query.try_call(args)?.is_some_and(|x| is_truthy(&x))),
+                Function::Call(call) => call.call(args).map(|_| true),
+            };
+        }
+        Ok(self.eval(expr)?.is_some_and(|x| is_truthy(&x)))
+    }
+
+    fn variable(&self, name: &str) -> anyhow::Result<Value> {
+        if let Some(value) = self.variables.get(name) {
+            return Ok(value.clone());
+        }
+        match name {
+            "host" => osi_fn!(GetHostCharacter)?.ok_or_else(|| anyhow!("no host character")),
+            "last" => bail!("$last is unset, no query ran yet"),
+            "selected" => bail!("$selected is unset, select an item, spell, etc. first"),
+            x => bail!("unknown variable '${x}'"),
+        }
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::None => false,
+        Value::Int(x) => *x != 0,
+        Value::Float(x) => *x != 0.0,
+        Value::String(x) => !x.is_empty(),
+    }
+}
+
+/// Facts of the database `name` with `arity` columns.
+fn database_rows(name: &str, arity: usize) -> anyhow::Result<Vec<Vec<Value>>> {
+    let globals = Globals::osiris_globals();
+    let (Some(fn_db), Some(nodes), Some(databases)) = (
+        globals.functions.as_opt().and_then(|x| x.as_opt()),
+        globals.nodes.as_opt().and_then(|x| x.as_opt()),
+        globals.databases.as_opt().and_then(|x| x.as_opt()),
+    ) else {
+        bail!("the Osiris databases aren't loaded, try loading a save");
+    };
+
+    let key = format!("{name}/{arity}");
+    let Some(db) = fn_db
+        .functions()
+        .find(|(k, f)| {
+            matches!(f.r#type, FunctionType::Database) && k.to_string().eq_ignore_ascii_case(&key)
+        })
+        .and_then(|(_, f)| f.database(nodes, databases))
+    else {
+        bail!("unable to find database '{name}' with {arity} columns");
+    };
+
+    Ok(db
+        .facts
+        .iter()
+        .map(|x| x.as_slice().iter().map(|x| Value::from_ffi(&x.as_argument())).collect())
+        .collect())
+}
+
+/// Newlines outside of parentheses and brackets separate statements like `;`.
+fn tokenize(text: &str) -> anyhow::Result<Vec<Token>> {
+    let is_word = |x: char| x.is_ascii_alphanumeric() || matches!(x, '_' | '-' | '.');
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\n' if depth == 0 => tokens.push(Token::Punct(';')),
+            x if x.is_whitespace() => (),
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|x| *x != '\n').is_some() {},
+            '"' => {
+                let mut str = String::new();
+                loop {
+                    match chars.next() {
+                        None => bail!("unterminated string"),
+                        Some('"') => break,
+                        Some('\\') => str.extend(chars.next()),
+                        Some(x) => str.push(x),
+                    }
+                }
+                tokens.push(Token::Str(str));
+            }
+            '$' => {
+                let mut name = String::new();
+                while let Some(x) = chars.next_if(|x| x.is_ascii_alphanumeric() || *x == '_') {
+                    name.push(x);
+                }
+                if name.is_empty() {
+                    bail!("expected a variable name after '$'");
+                }
+                tokens.push(Token::Variable(name));
+            }
+            x if is_word(x) => {
+                let mut word = x.to_string();
+                while let Some(x) = chars.next_if(|x| is_word(*x)) {
+                    word.push(x);
+                }
+                tokens.push(Token::Word(word));
+            }
+            '(' | '[' => {
+                depth += 1;
+                tokens.push(Token::Punct(char));
+            }
+            ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                tokens.push(Token::Punct(char));
+            }
+            '{' | '}' | ',' | ';' | '=' => tokens.push(Token::Punct(char)),
+            x => bail!("unexpected character '{x}'"),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, char: char) -> anyhow::Result<()> {
+        match self.next() {
+            Some(Token::Punct(x)) if x == char => Ok(()),
+            Some(x) => bail!("expected '{char}', found {}", describe(&x)),
+            None => bail!("expected '{char}', found the end of the script"),
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.eat(&Token::Punct(';')) {}
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(x)) if x == keyword)
+    }
+
+    fn script(&mut self) -> anyhow::Result<Vec<Stmt>> {
+        let stmts = self.statements()?;
+        if let Some(x) = self.peek() {
+            bail!("unexpected {}", describe(x));
+        }
+        Ok(stmts)
+    }
+
+    /// Statements until the end of the script or a closing brace.
+    fn statements(&mut self) -> anyhow::Result<Vec<Stmt>> {
+        let mut stmts = Vec::new();
+        loop {
+            self.skip_separators();
+            if matches!(self.peek(), None | Some(Token::Punct('}'))) {
+                return Ok(stmts);
+            }
+            let stmt = self.statement()?;
+            let is_block = matches!(stmt, Stmt::For(..) | Stmt::If(..) | Stmt::Macro(..));
+            stmts.push(stmt);
+            if !is_block && !matches!(self.peek(), None | Some(Token::Punct(';' | '}'))) {
+                bail!("expected ';', found {}", describe(self.peek().unwrap()));
+            }
+        }
+    }
+
+    fn block(&mut self) -> anyhow::Result<Vec<Stmt>> {
+        self.skip_separators();
+        self.expect('{')?;
+        let stmts = self.statements()?;
+        self.expect('}')?;
+        Ok(stmts)
+    }
+
+    fn statement(&mut self) -> anyhow::Result<Stmt> {
+        let Some(Token::Word(word)) = self.peek() else {
+            return Ok(Stmt::Expr(self.expr()?));
+        };
+        match word.as_str() {
+            "let" => {
+                self.pos += 1;
+                let name = self.name()?;
+                self.expect('=')?;
+                Ok(Stmt::Let(name, self.expr()?))
+            }
+            "for" => {
+                self.pos += 1;
+                let mut vars = vec![self.variable()?];
+                while self.eat(&Token::Punct(',')) {
+                    vars.push(self.variable()?);
+                }
+                if !self.is_keyword("in") {
+                    bail!("expected 'in' after the loop variables");
+                }
+                self.pos += 1;
+                let iterable = if self.eat(&Token::Punct('[')) {
+                    Iterable::List(self.list(']')?)
+                } else {
+                    Iterable::Database(self.name()?)
+                };
+                Ok(Stmt::For(vars, iterable, self.block()?))
+            }
+            "if" => {
+                self.pos += 1;
+                let condition = self.expr()?;
+                let then = self.block()?;
+                let save = self.pos;
+                self.skip_separators();
+                let otherwise = if self.is_keyword("else") {
+                    self.pos += 1;
+                    if self.is_keyword("if") {
+                        vec![self.statement()?]
+                    } else {
+                        self.block()?
+                    }
+                } else {
+                    self.pos = save;
+                    Vec::new()
+                };
+                Ok(Stmt::If(condition, then, otherwise))
+            }
+            "macro" => {
+                self.pos += 1;
+                let name = self.name()?;
+                self.expect('(')?;
+                let mut params = Vec::new();
+                if !self.eat(&Token::Punct(')')) {
+                    loop {
+                        params.push(self.variable()?);
+                        if self.eat(&Token::Punct(')')) {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Stmt::Macro(name, params, self.block()?))
+            }
+            "include" => {
+                self.pos += 1;
+                match self.next() {
+                    Some(Token::Str(path)) => Ok(Stmt::Include(path)),
+                    _ => bail!("expected a quoted path after 'include'"),
+                }
+            }
+            _ => Ok(Stmt::Expr(self.expr()?)),
+        }
+    }
+
+    fn name(&mut self) -> anyhow::Result<String> {
+        match self.next() {
+            Some(Token::Word(x) | Token::Variable(x)) => Ok(x),
+            Some(x) => bail!("expected a name, found {}", describe(&x)),
+            None => bail!("expected a name, found the end of the script"),
+        }
+    }
+
+    fn variable(&mut self) -> anyhow::Result<String> {
+        match self.next() {
+            Some(Token::Variable(x)) => Ok(x),
+            Some(x) => bail!("expected a $variable, found {}", describe(&x)),
+            None => bail!("expected a $variable, found the end of the script"),
+        }
+    }
+
+    fn list(&mut self, end: char) -> anyhow::Result<Vec<Expr>> {
+        let mut items = Vec::new();
+        if self.eat(&Token::Punct(end)) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expr()?);
+            if self.eat(&Token::Punct(end)) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn expr(&mut self) -> anyhow::Result<Expr> {
+        match self.next() {
+            Some(Token::Str(x)) => Ok(Expr::Value(Value::String(CString::new(x)?))),
+            Some(Token::Variable(x)) => Ok(Expr::Variable(x)),
+            Some(Token::Word(x)) if self.eat(&Token::Punct('(')) => {
+                if !x.chars().all(|x| x.is_ascii_alphanumeric() || x == '_') {
+                    bail!("invalid function name '{x}'");
+                }
+                Ok(Expr::Call(x, self.list(')')?))
+            }
+            Some(Token::Word(x)) => Ok(Expr::Value(parse_word(&x)?)),
+            Some(x) => bail!("expected a value, found {}", describe(&x)),
+            None => bail!("expected a value, found the end of the script"),
+        }
+    }
+}
+
+/// `None`, numbers, and anything else as a bare string, e.g. a GUID.
+fn parse_word(word: &str) -> anyhow::Result<Value> {
+    if word == "None" {
+        return Ok(Value::None);
+    }
+    if word.starts_with(|x: char| x.is_ascii_digit() || x == '-' || x == '.') {
+        if let Ok(x) = word.parse::<i64>() {
+            return Ok(Value::Int(x));
+        }
+        if let Ok(x) = word.parse::<f32>() {
+            return Ok(Value::Float(x));
+        }
+    }
+    Ok(Value::String(CString::new(word)?))
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(x) => format!("'{x}'"),
+        Token::Variable(x) => format!("'${x}'"),
+        Token::Str(x) => format!("\"{x}\""),
+        Token::Punct(';') => "the end of the statement".into(),
+        Token::Punct(x) => format!("'{x}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(x: &str) -> Token {
+        Token::Word(x.into())
+    }
+
+    /// Output of `text` run in a new environment.
+    fn run(text: &str) -> anyhow::Result<String> {
+        let mut out = String::new();
+        ScriptEnv::default().run(text, &mut out)?;
+        Ok(out)
+    }
+
+    fn run_err(text: &str) -> String {
+        run(text).expect_err(text).to_string()
+    }
+
+    #[test]
+    fn tokenize_tokens() {
+        let tokens = tokenize("let $x = \"a \\\"b\\\"\" // comment\nFoo(1, -2.5)").unwrap();
+        assert_eq!(tokens, [
+            word("let"),
+            Token::Variable("x".into()),
+            Token::Punct('='),
+            Token::Str("a \"b\"".into()),
+            Token::Punct(';'),
+            word("Foo"),
+            Token::Punct('('),
+            word("1"),
+            Token::Punct(','),
+            word("-2.5"),
+            Token::Punct(')'),
+        ]);
+    }
+
+    #[test]
+    fn tokenize_newlines_in_parentheses() {
+        let tokens = tokenize("Foo(1,\n[2,\n3])\nBar()").unwrap();
+        assert_eq!(tokens.iter().filter(|x| **x == Token::Punct(';')).count(), 1);
+        assert_eq!(tokens[tokens.len() - 4], Token::Punct(';'));
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        assert_eq!(tokenize("\"abc").unwrap_err().to_string(), "unterminated string");
+        assert_eq!(tokenize("$ x").unwrap_err().to_string(), "expected a variable name after '$'");
+        assert_eq!(tokenize("a @ b").unwrap_err().to_string(), "unexpected character '@'");
+    }
+
+    #[test]
+    fn words() {
+        assert!(matches!(parse_word("None").unwrap(), Value::None));
+        assert!(matches!(parse_word("42").unwrap(), Value::Int(42)));
+        assert!(matches!(parse_word("-7").unwrap(), Value::Int(-7)));
+        assert!(matches!(parse_word("1.5").unwrap(), Value::Float(x) if x == 1.5));
+        let guid = "S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c";
+        assert!(matches!(parse_word(guid).unwrap(), Value::String(x) if x.to_str() == Ok(guid)));
+        let guid = "2c76687d-93a2-477b-8b18-8a14b549304c";
+        assert!(matches!(parse_word(guid).unwrap(), Value::String(_)));
+    }
+
+    #[test]
+    fn call_arguments() {
+        let text = "ApplyStatus($host, S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c, \
+                    1e4f6b2a-0d1c-4c3e-9f7a-5b2d8c6e0a13, -5, -0.5, \"a, (b)\", None)";
+        let Expr::Call(name, args) = Parser::new(tokenize(text).unwrap()).expr().unwrap() else {
+            panic!("expected a call");
+        };
+        assert_eq!(name, "ApplyStatus");
+        let args = args
+            .iter()
+            .map(|x| match x {
+                Expr::Value(x) => format!("{} {x}", x.type_str()),
+                Expr::Variable(x) => format!("${x}"),
+                Expr::Call(x, _) => format!("{x}()"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(args, [
+            "$host",
+            "String S_Player_Karlach_2c76687d-93a2-477b-8b18-8a14b549304c",
+            "String 1e4f6b2a-0d1c-4c3e-9f7a-5b2d8c6e0a13",
+            "Integer -5",
+            "Real -0.5",
+            "String a, (b)",
+            "None None",
+        ]);
+    }
+
+    #[test]
+    fn sequencing() {
+        assert_eq!(run("let $a = 1; let $b = $a\n$b; \"two\"\n\n3").unwrap(), "1\ntwo\n3\n");
+        assert_eq!(run("").unwrap(), "");
+        assert_eq!(run(";;\n;").unwrap(), "");
+    }
+
+    #[test]
+    fn variables_are_kept_between_runs() {
+        let mut env = ScriptEnv::default();
+        let mut out = String::new();
+        env.run("let $a = 5", &mut out).unwrap();
+        env.run("$a", &mut out).unwrap();
+        assert_eq!(out, "5\n");
+        assert_eq!(run_err("$a"), "unknown variable '$a'");
+    }
+
+    #[test]
+    fn for_over_list() {
+        assert_eq!(run("for $x in [1, 2, 3] { let $y = $x }; $y").unwrap(), "3\n");
+        assert_eq!(run("for $x in [] { let $y = $x }\n1").unwrap(), "1\n");
+        assert_eq!(run_err("for $x, $y in [1] {}"), "lists are iterated with a single variable");
+    }
+
+    #[test]
+    fn for_variables_are_scoped() {
+        assert_eq!(run_err("for $x in [1] {}; $x"), "unknown variable '$x'");
+        let text = "let $x = outer; for $x in [1] { let $y = $x }; $x; $y";
+        assert_eq!(run(text).unwrap(), "outer\n1\n");
+    }
+
+    #[test]
+    fn for_variables_are_restored_on_error() {
+        let mut env = ScriptEnv::default();
+        let mut out = String::new();
+        env.run("let $x = outer", &mut out).unwrap();
+        assert!(env.run("for $x in [1] { $missing }", &mut out).is_err());
+        env.run("$x", &mut out).unwrap();
+        assert_eq!(out, "outer\n");
+    }
+
+    #[test]
+    fn if_else() {
+        assert_eq!(run("if 1 { let $r = a } else { let $r = b }; $r").unwrap(), "a\n");
+        assert_eq!(run("if 0 { let $r = a } else { let $r = b }; $r").unwrap(), "b\n");
+        assert_eq!(run("if \"\" { let $r = a }\nelse { let $r = b }\n$r").unwrap(), "b\n");
+        let text = "if None { let $r = a } else if 2.5 { let $r = b } else { let $r = c }; $r";
+        assert_eq!(run(text).unwrap(), "b\n");
+        assert_eq!(run("if 0 { 1 }\n2").unwrap(), "2\n");
+    }
+
+    #[test]
+    fn macros() {
+        let mut env = ScriptEnv::default();
+        let mut out = String::new();
+        env.run("macro second($a, $b) { $b }\nlet $a = outer\nsecond(1, 2); $a", &mut out).unwrap();
+        assert_eq!(out, "2\nouter\n");
+        assert_eq!(env.macros().collect::<Vec<_>>(), [("second", String::from("second($a, $b)"))]);
+        assert_eq!(
+            env.run("second(1)", &mut out).unwrap_err().to_string(),
+            "macro second: expected 2 arguments, got 1"
+        );
+
+        assert_eq!(run_err("macro none() { }; let $x = none()"), "none doesn't return a value");
+        assert_eq!(run_err("macro f() { f() }; f()"), "too many nested macros or includes");
+    }
+
+    #[test]
+    fn include() {
+        let dir = std::env::temp_dir().join(format!("script-include-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.osiscript"), "let $a = 1\nmacro id($x) { $x }\n").unwrap();
+
+        let mut env = ScriptEnv { dir: Some(dir.clone()), ..Default::default() };
+        let mut out = String::new();
+        env.run("include \"lib\"; id($a)", &mut out).unwrap();
+        assert_eq!(out, "1\n");
+        let err = env.run("include \"missing\"", &mut out).unwrap_err().to_string();
+        assert!(err.starts_with("failed to read"), "{err}");
+
+        std::fs::write(dir.join("bad.osiscript"), "let = 1").unwrap();
+        let err = env.run("include \"bad.osiscript\"", &mut out).unwrap_err().to_string();
+        assert!(err.ends_with("bad.osiscript: expected a name, found '='"), "{err}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (text, err) in [
+            ("let = 1", "expected a name, found '='"),
+            ("let $a 1", "expected '=', found '1'"),
+            ("1 2", "expected ';', found '2'"),
+            ("}", "unexpected '}'"),
+            ("Foo(1 2)", "expected ',', found '2'"),
+            ("Foo(1,", "expected a value, found the end of the script"),
+            ("Foo-bar(1)", "invalid function name 'Foo-bar'"),
+            ("for $x [1] {}", "expected 'in' after the loop variables"),
+            ("for x in [1] {}", "expected a $variable, found 'x'"),
+            ("if 1 let", "expected '{', found 'let'"),
+            ("if 1 { 2", "expected '}', found the end of the script"),
+            ("macro m(a) {}", "expected a $variable, found 'a'"),
+            ("include lib", "expected a quoted path after 'include'"),
+        ] {
+            assert_eq!(run_err(text), err, "{text}");
+        }
+    }
+}
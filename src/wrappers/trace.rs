@@ -0,0 +1,255 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    game_definitions::{FunctionType, GamePtr, OsiArgumentDesc},
+    globals::Globals,
+    menu::search::export::ExportValue,
+    wrappers::osiris::Value,
+};
+
+/// Entries past this are dropped, oldest first.
+pub(crate) const DEFAULT_TRACE_CAPACITY: usize = 10000;
+/// Unknown handles reload the function names at most this often.
+const NAMES_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether the [`Tracer`] records, read by the hooks without locking so the
+/// trace costs nothing until it's started.
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TraceKind {
+    Event,
+    Call,
+    Query,
+}
+
+impl Display for TraceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceKind::Event => f.write_str("Event"),
+            TraceKind::Call => f.write_str("Call"),
+            TraceKind::Query => f.write_str("Query"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TraceEntry {
+    /// Since the trace was started or cleared
+    pub time: Duration,
+    pub kind: TraceKind,
+    pub name: String,
+    pub function_type: Option<FunctionType>,
+    pub args: Vec<Value>,
+    pub result: bool,
+}
+
+impl TraceEntry {
+    pub fn args_str(&self) -> String {
+        self.args.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn export(&self) -> ExportValue {
+        let value = |x: &dyn Display| ExportValue::Value(x.to_string());
+        ExportValue::Object(vec![
+            ("Time".into(), value(&format!("{:.3}", self.time.as_secs_f64()))),
+            ("Kind".into(), value(&self.kind)),
+            ("Function".into(), value(&self.name)),
+            ("Type".into(), self.function_type.map_or(ExportValue::Null, |x| value(&x))),
+            ("Arguments".into(), ExportValue::List(self.args.iter().map(|x| value(x)).collect())),
+            ("Result".into(), value(&self.result)),
+        ])
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TracedFunction {
+    name: String,
+    r#type: FunctionType,
+}
+
+/// Ring buffer of the Osiris events, calls and queries seen by the hooks.
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    pub entries: VecDeque<TraceEntry>,
+    pub capacity: usize,
+    /// Set by the Trace tab, until it's unset only remote subscribers record
+    paused: bool,
+    /// Remote connections subscribed to the trace
+    subscribers: usize,
+    /// Only functions matching one of these are recorded, all if empty
+    pub include: Vec<String>,
+    /// Functions matching one of these are never recorded
+    pub exclude: Vec<String>,
+    /// Entries recorded since the start, including the ones dropped or
+    /// cleared since, for readers that only want new ones
+    pub recorded: usize,
+    start: Option<Instant>,
+    by_handle: BTreeMap<u32, TracedFunction>,
+    by_id: BTreeMap<u32, TracedFunction>,
+    last_names_load: Option<Instant>,
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracer {
+    pub const fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: DEFAULT_TRACE_CAPACITY,
+            paused: true,
+            subscribers: 0,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            recorded: 0,
+            start: None,
+            by_handle: BTreeMap::new(),
+            by_id: BTreeMap::new(),
+            last_names_load: None,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.update_recording();
+    }
+
+    /// Counts a remote subscriber, which records even while paused.
+    pub fn subscribe(&mut self, subscribed: bool) {
+        if subscribed {
+            self.subscribers += 1;
+        } else {
+            self.subscribers = self.subscribers.saturating_sub(1);
+        }
+        self.update_recording();
+    }
+
+    fn update_recording(&self) {
+        RECORDING.store(!self.paused || self.subscribers > 0, Ordering::Relaxed);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.start = None;
+    }
+
+    /// Called by the hooks after the original function returned, so query
+    /// out parameters are filled in.
+    pub fn record(
+        &mut self,
+        kind: TraceKind,
+        name: String,
+        function_type: Option<FunctionType>,
+        params: GamePtr<OsiArgumentDesc>,
+        result: bool,
+    ) {
+        if !is_recording() || !self.is_included(&name, kind, function_type) {
+            return;
+        }
+        let now = Instant::now();
+        let start = *self.start.get_or_insert(now);
+
+        let args = params
+            .as_opt()
+            .map(|x| x.iter().map(|x| Value::from_ffi(&x)).collect())
+            .unwrap_or_default();
+
+        while self.entries.len() >= self.capacity.max(1) {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(TraceEntry {
+            time: now - start,
+            kind,
+            name,
+            function_type,
+            args,
+            result,
+        });
+        self.recorded += 1;
+    }
+
+    /// Entries recorded after the first `recorded` ones, as many as are
+    /// still in the buffer.
+    pub fn since(&self, recorded: usize) -> impl Iterator<Item = &TraceEntry> {
+        let new = self.recorded.saturating_sub(recorded).min(self.entries.len());
+        self.entries.iter().skip(self.entries.len() - new)
+    }
+
+    /// Name and type of the function behind an event id or call handle,
+    /// unknown ones are named after the handle.
+    pub fn resolve(&mut self, handle: u32) -> (String, Option<FunctionType>) {
+        match self.function(handle) {
+            Some(f) => (f.name.clone(), Some(f.r#type)),
+            None => (format!("#{handle:x}"), None),
+        }
+    }
+
+    /// Filters match function names by substring and kinds or function
+    /// types by name, both case insensitive.
+    fn is_included(&self, name: &str, kind: TraceKind, r#type: Option<FunctionType>) -> bool {
+        let name = name.to_lowercase();
+        let kind = kind.to_string();
+        let r#type = r#type.map(|x| x.to_string());
+        let matches = |filter: &String| {
+            name.contains(&filter.to_lowercase())
+                || kind.eq_ignore_ascii_case(filter)
+                || r#type.as_ref().is_some_and(|x| x.eq_ignore_ascii_case(filter))
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+
+    /// Events pass the function id while calls and queries pass the handle.
+    fn function(&mut self, key: u32) -> Option<&TracedFunction> {
+        if !self.by_handle.contains_key(&key)
+            && !self.by_id.contains_key(&key)
+            && self.last_names_load.map_or(true, |x| x.elapsed() >= NAMES_RELOAD_INTERVAL)
+        {
+            self.load_names();
+        }
+        self.by_handle.get(&key).or_else(|| self.by_id.get(&key))
+    }
+
+    fn load_names(&mut self) {
+        self.last_names_load = Some(Instant::now());
+        let Some(fn_db) = Globals::osiris_globals_opt()
+            .and_then(|x| x.functions.as_opt())
+            .and_then(|x| x.as_opt())
+        else {
+            return;
+        };
+
+        for (name, f) in fn_db.functions() {
+            let name = name.to_string();
+            let function = TracedFunction {
+                name: name.rsplit_once('/').map_or(name.as_str(), |x| x.0).into(),
+                r#type: f.r#type,
+            };
+            self.by_id.insert(f.osi_function_id, function.clone());
+            self.by_handle.insert(f.handle(), function);
+        }
+    }
+}
+
+pub(crate) fn is_recording() -> bool {
+    RECORDING.load(Ordering::Relaxed)
+}
+
+/// Splits a comma separated filter list.
+pub(crate) fn parse_filters(text: &str) -> Vec<String> {
+    text.split(',').map(str::trim).filter(|x| !x.is_empty()).map(String::from).collect()
+}